@@ -0,0 +1,34 @@
+//! Generation throughput with the board stored column by column and row by
+//! row, at 150 x 150 and 500 x 500.
+use std::time::Instant;
+
+use canvas::{Game, Layout};
+
+fn random_game(size: i16, layout: Layout) -> Game {
+    let mut game = Game::headless(size);
+    game.set_seed(1);
+    game.restart("random").expect("random is a known mode");
+    game.set_layout(layout);
+    game
+}
+
+fn bench(label: &str, mut game: Game, generations: u32) {
+    let start = Instant::now();
+    for _ in 0..generations {
+        game.step();
+    }
+    let per_generation = start.elapsed().as_secs_f64() * 1000.0 / generations as f64;
+    println!("{:<44} {:>9.3} ms/generation ({} alive)", label, per_generation, game.population());
+}
+
+fn main() {
+    for &(size, generations) in &[(150, 200), (500, 20)] {
+        for &layout in &[Layout::ColumnMajor, Layout::RowMajor] {
+            bench(&format!("{0}x{0} {1:?}", size, layout), random_game(size, layout), generations);
+            // a wall sends every cell down the cell by cell path
+            let mut game = random_game(size, layout);
+            game.set_wall(0, 0, true);
+            bench(&format!("{0}x{0} {1:?}, cell by cell", size, layout), game, generations);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Generations of a large, sparse board with a few gliders, with and
+//! without the neighbour cache.
+use std::time::Instant;
+
+use canvas::Game;
+
+const SIZE: i16 = 1000;
+const GLIDER: [(i16, i16); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+fn bench(cache: bool, generations: u32) {
+    let mut game = Game::headless(SIZE);
+    // the cache only serves the cell by cell path, which a wall forces
+    game.set_wall(SIZE - 1, SIZE - 1, true);
+    for glider in 0..8 {
+        let (x, y) = (100 + glider * 100, 100 + glider * 37);
+        for &(dx, dy) in GLIDER.iter() {
+            game.set_cell(x + dx, y + dy, true).expect("the glider is on the board");
+        }
+    }
+    game.set_neighbour_cache(cache);
+    let start = Instant::now();
+    for _ in 0..generations {
+        game.step();
+    }
+    let per_generation = start.elapsed().as_secs_f64() * 1000.0 / generations as f64;
+    let label = if cache { "neighbour cache" } else { "no cache" };
+    println!("{:<20} {:>9.3} ms/generation ({} alive)", label, per_generation, game.population());
+}
+
+fn main() {
+    bench(false, 20);
+    bench(true, 20);
+}
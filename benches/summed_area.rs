@@ -0,0 +1,29 @@
+//! Radius 5 Larger than Life generations with neighbour counts taken cell
+//! by cell and from the summed-area table.
+use std::time::Instant;
+
+use canvas::{Game, Rule};
+
+// Bosco's rule
+const RULE: &str = "R5,C0,M1,S34..58,B34..45,NM";
+
+fn bench(label: &str, summed_area: bool, size: i16, generations: u32) {
+    let mut game = Game::headless(size);
+    game.set_seed(1);
+    game.restart("random").expect("random is a known mode");
+    game.use_rule(Rule::parse(RULE).expect("the rule parses"));
+    game.set_summed_area(summed_area);
+    let start = Instant::now();
+    for _ in 0..generations {
+        game.step();
+    }
+    let per_generation = start.elapsed().as_secs_f64() * 1000.0 / generations as f64;
+    println!("{:<32} {:>9.3} ms/generation ({} alive)", label, per_generation, game.population());
+}
+
+fn main() {
+    for &(size, generations) in &[(100, 20), (300, 5)] {
+        bench(&format!("{0}x{0} naive", size), false, size, generations);
+        bench(&format!("{0}x{0} summed-area", size), true, size, generations);
+    }
+}
@@ -1,3 +1,5 @@
 // For more comments about what's going on here, check out the `hello_world`
 // example.
 const rust = import('./pkg')
+  .then(rust => rust.start('canvas', 150, 150, 4, rust.RenderBackend.Canvas2d))
+  .catch(console.error)
@@ -0,0 +1,72 @@
+use std::cmp::{max, min};
+use wasm_bindgen::prelude::*;
+
+use crate::bits::BitGrid;
+use crate::game::{Game, UpdateMode};
+
+impl Game {
+    // the board the open generation started from: a sequential update
+    // overwrites `state` as it goes, so it works from a copy
+    pub(crate) fn snapshot_generation_base(&mut self) {
+        self.generation_base = match self.update_mode {
+            UpdateMode::Sequential => self.state.clone(),
+            UpdateMode::Synchronous => BitGrid::default(),
+        };
+    }
+    // called on commit, before the open generation replaces the board
+    pub(crate) fn record_activity(&mut self) {
+        let base = if self.generation_base.is_empty() { &self.state } else { &self.generation_base };
+        self.last_changes.clear();
+        self.last_births.clear();
+        // column by column, as the board is packed
+        for (col_num, row_num, alive) in base.changed_cells(&self.interim_state) {
+            self.last_changes.push((col_num, row_num));
+            // only kept for the birth flash
+            if alive && self.flash_color.is_some() {
+                self.last_births.push((col_num, row_num));
+            }
+        }
+        self.last_activity = self.last_changes.len() as u32;
+        self.generation_base = BitGrid::default();
+    }
+    /// Centre cell of the `block` x `block` tile (tiles start at the top
+    /// left corner) with the most births plus deaths in the last committed
+    /// generation, the first in row order on a tie, or `None` when nothing
+    /// changed.
+    pub fn hotspot(&self, block: i16) -> Option<(i16, i16)> {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        let block = max(block, 1) as usize;
+        let (across, down) = (width.div_ceil(block), height.div_ceil(block));
+        let mut counts = vec![0u32; across * down];
+        for &(col, row) in &self.last_changes {
+            counts[row / block * across + col / block] += 1;
+        }
+        let (index, &count) = counts
+            .iter()
+            .enumerate()
+            .fold((0, &0), |best, tile| if tile.1 > best.1 { tile } else { best });
+        if count == 0 {
+            return None;
+        }
+        let centre = |tile: usize, size: usize| {
+            let start = tile * block;
+            (start + (min(start + block, size) - start) / 2) as i16
+        };
+        Some((centre(index % across, width), centre(index / across, height)))
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// `hotspot` for JS, as `[x, y]`, or an empty array when nothing
+    /// changed.
+    #[wasm_bindgen(js_name = hotspot)]
+    pub fn hotspot_js(&self, block: i16) -> Vec<i16> {
+        self.hotspot(block).map_or(vec![], |(x, y)| vec![x, y])
+    }
+    /// Births plus deaths in the last committed generation; 0 means the
+    /// board stopped changing. Replacing the board resets it to 0.
+    pub fn last_activity(&self) -> u32 {
+        self.last_activity
+    }
+}
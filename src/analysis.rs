@@ -0,0 +1,254 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+// start and length of the shortest span covering every occupied index;
+// with `wrap` it may run past the end and continue from 0
+fn extent(occupied: &[bool], wrap: bool) -> Option<(i16, i16)> {
+    let first = occupied.iter().position(|&taken| taken)?;
+    let last = occupied.iter().rposition(|&taken| taken)?;
+    let (mut start, mut len) = (first, last - first + 1);
+    if wrap {
+        // the span starts right after the longest run of free indices,
+        // counting the run that wraps around the end
+        let size = occupied.len();
+        let mut gap = (size - 1 - last) + first;
+        let mut run = 0;
+        for (index, &taken) in occupied.iter().enumerate().take(last + 1).skip(first) {
+            if taken {
+                if run > gap {
+                    gap = run;
+                    start = index;
+                }
+                run = 0;
+            } else {
+                run += 1;
+            }
+        }
+        len = size - gap;
+    }
+    Some((start as i16, len as i16))
+}
+
+impl Game {
+    /// `(x, y)` of every live cell in row-major order (left to right, then
+    /// top to bottom), the order `live_cells` lists them in, without
+    /// collecting them first.
+    pub fn iter_live(&self) -> impl Iterator<Item = (i16, i16)> + '_ {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        (0..height).flat_map(move |row| {
+            (0..width)
+                .filter(move |&col| self.state.get(col, row))
+                .map(move |col| (col as i16, row as i16))
+        })
+    }
+    /// Average `(x, y)` of the live cells, or `None` on an empty board. On
+    /// a toroidal board the average is ambiguous: a pattern straddling the
+    /// seam averages to somewhere in the middle of the board, far from any
+    /// of its cells, so this treats the board as bounded.
+    pub fn center_of_mass(&self) -> Option<(f64, f64)> {
+        let (mut x, mut y, mut count) = (0.0, 0.0, 0.0);
+        for (col_num, row_num) in self.state.live_cells() {
+            x += col_num as f64;
+            y += row_num as f64;
+            count += 1.0;
+        }
+        if count == 0.0 {
+            None
+        } else {
+            Some((x / count, y / count))
+        }
+    }
+    // 8-connected labels per cell, `labels[x][y]`, 0 for dead cells and
+    // 1.. for components in scan order; with `wrap` opposite edges touch
+    pub(crate) fn label_components(&self, wrap: bool) -> (Vec<Vec<u32>>, u32) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let mut labels = vec![vec![0u32; height as usize]; width as usize];
+        let mut count = 0;
+        for col in 0..width as usize {
+            for row in 0..height as usize {
+                if !self.state.get(col, row) || labels[col][row] != 0 {
+                    continue;
+                }
+                count += 1;
+                labels[col][row] = count;
+                let mut stack = vec![(col as i16, row as i16)];
+                while let Some((x, y)) = stack.pop() {
+                    for dx in -1..=1 {
+                        for dy in -1..=1 {
+                            let (mut nx, mut ny) = (x + dx, y + dy);
+                            if wrap {
+                                nx = nx.rem_euclid(width);
+                                ny = ny.rem_euclid(height);
+                            } else if !self.grid.contains(nx, ny) {
+                                continue;
+                            }
+                            let (nx, ny) = (nx as usize, ny as usize);
+                            if self.state.get(nx, ny) && labels[nx][ny] == 0 {
+                                labels[nx][ny] = count;
+                                stack.push((nx as i16, ny as i16));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        (labels, count)
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Grayscale `target_width` x `target_height` snapshot, one byte per
+    /// pixel row by row, where each pixel is the live fraction (0 to 255) of
+    /// the block of cells it covers. Meant for a small navigation canvas.
+    pub fn minimap(&self, target_width: u16, target_height: u16) -> Vec<u8> {
+        let (width, height) = (target_width as usize, target_height as usize);
+        // span of the `size` cells along an axis covered by output pixel
+        // `index` out of `len`
+        let span = |index: usize, len: usize, size: usize| {
+            let start = index * size / len;
+            let end = ((index + 1) * size / len).max(start + 1).min(size);
+            (start as i16, (end - start) as i16)
+        };
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let (row, rows) = span(y, height, self.grid.height as usize);
+            for x in 0..width {
+                let (col, cols) = span(x, width, self.grid.width as usize);
+                pixels.push((self.tile_density(col, row, cols, rows) * 255.0).round() as u8);
+            }
+        }
+        pixels
+    }
+    /// Fraction of the board that is alive, from 0 to 1.
+    pub fn density(&self) -> f64 {
+        self.count_population() as f64 / (self.grid.width as f64 * self.grid.height as f64)
+    }
+    /// Generations committed since the game was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// Live cells on the board. The count is kept as cells change, so this
+    /// is cheap enough to call every frame.
+    pub fn population(&self) -> u32 {
+        self.count_population()
+    }
+    /// `{ generation, population, density, activity, paused }` for a page
+    /// to show alongside the board, where `activity` is the cells the last
+    /// generation changed.
+    pub fn stats(&self) -> Result<JsValue, JsValue> {
+        let stats = js_sys::Object::new();
+        js_sys::Reflect::set(&stats, &"generation".into(), &JsValue::from(self.generation as f64))?;
+        js_sys::Reflect::set(&stats, &"population".into(), &JsValue::from(self.count_population()))?;
+        js_sys::Reflect::set(&stats, &"density".into(), &JsValue::from(self.density()))?;
+        js_sys::Reflect::set(&stats, &"activity".into(), &JsValue::from(self.last_activity))?;
+        js_sys::Reflect::set(&stats, &"paused".into(), &JsValue::from(self.is_paused()))?;
+        Ok(stats.into())
+    }
+    /// Connected cluster of every cell, row by row: 0 for dead cells and
+    /// 1, 2, ... for the clusters (cells touching along a side or a corner)
+    /// in scan order. Under `EdgeMode::Wrap` cells on opposite edges
+    /// touch, so a cluster straddling the seam of the torus counts once.
+    pub fn components(&self) -> Vec<u32> {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        let (labels, _) = self.label_components(self.wraps_edges());
+        let mut components = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col_labels in &labels {
+                components.push(col_labels[row]);
+            }
+        }
+        components
+    }
+    /// `center_of_mass` for JS, as `[x, y]`, or an empty array on an empty
+    /// board.
+    #[wasm_bindgen(js_name = center_of_mass)]
+    pub fn center_of_mass_js(&self) -> Vec<f64> {
+        self.center_of_mass().map_or(vec![], |(x, y)| vec![x, y])
+    }
+    /// Number of connected clusters, as `components` counts them.
+    pub fn component_count(&self) -> u32 {
+        self.label_components(self.wraps_edges()).1
+    }
+    /// Smallest `[x, y, width, height]` rectangle holding every live cell,
+    /// or an empty array when there is none. Under `EdgeMode::Wrap` the
+    /// rectangle may cross the seam, so `x + width` or `y + height` can go
+    /// past the edge and continue from 0.
+    pub fn bounding_box(&self) -> Vec<i16> {
+        let wrap = self.wraps_edges();
+        let mut cols = vec![false; self.grid.width as usize];
+        let mut rows = vec![false; self.grid.height as usize];
+        for (col_num, row_num) in self.state.live_cells() {
+            cols[col_num] = true;
+            rows[row_num] = true;
+        }
+        match (extent(&cols, wrap), extent(&rows, wrap)) {
+            (Some((x, width)), Some((y, height))) => vec![x, y, width, height],
+            _ => vec![],
+        }
+    }
+    /// Live cells as a flat `[x0, y0, x1, y1, ...]` list in row-major order,
+    /// much smaller than `cells` on a sparse board.
+    pub fn live_cells(&self) -> Vec<i16> {
+        let mut cells = vec![];
+        for (x, y) in self.iter_live() {
+            cells.push(x);
+            cells.push(y);
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edges::EdgeMode;
+    use crate::game::Game;
+
+    fn board(size: i16, cells: &[(usize, usize)], edge_mode: EdgeMode) -> Game {
+        let mut game = Game::headless(size);
+        game.set_edge_mode(edge_mode);
+        for &(x, y) in cells {
+            game.state.set(x, y, true);
+        }
+        game
+    }
+
+    #[test]
+    fn cluster_across_the_side_seam_is_one_component() {
+        let block = [(9, 4), (9, 5), (0, 4), (0, 5)];
+        let bounded = board(10, &block, EdgeMode::Dead);
+        assert_eq!(bounded.component_count(), 2);
+        assert_eq!(bounded.bounding_box(), vec![0, 4, 10, 2]);
+
+        let torus = board(10, &block, EdgeMode::Wrap);
+        assert_eq!(torus.component_count(), 1);
+        assert_eq!(torus.bounding_box(), vec![9, 4, 2, 2]);
+        let components = torus.components();
+        assert_eq!(components[4 * 10 + 9], components[4 * 10]);
+    }
+
+    #[test]
+    fn cluster_across_the_top_seam_is_one_component() {
+        let blinker = [(3, 9), (3, 0), (3, 1)];
+        assert_eq!(board(10, &blinker, EdgeMode::Dead).component_count(), 2);
+        let torus = board(10, &blinker, EdgeMode::Wrap);
+        assert_eq!(torus.component_count(), 1);
+        assert_eq!(torus.bounding_box(), vec![3, 9, 1, 3]);
+    }
+
+    #[test]
+    fn cluster_across_the_corner_is_one_component() {
+        let corners = [(0, 0), (7, 0), (0, 7), (7, 7)];
+        assert_eq!(board(8, &corners, EdgeMode::Dead).component_count(), 4);
+        let torus = board(8, &corners, EdgeMode::Wrap);
+        assert_eq!(torus.component_count(), 1);
+        assert_eq!(torus.bounding_box(), vec![7, 7, 2, 2]);
+    }
+
+    #[test]
+    fn mirrored_edges_do_not_join_clusters() {
+        let block = [(9, 4), (9, 5), (0, 4), (0, 5)];
+        assert_eq!(board(10, &block, EdgeMode::Mirror).component_count(), 2);
+    }
+}
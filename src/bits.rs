@@ -0,0 +1,301 @@
+use wasm_bindgen::prelude::*;
+
+/// How a board is laid out in memory.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Layout {
+    /// Column by column, so radius 1 rules step a word of a column at a
+    /// time.
+    #[default]
+    ColumnMajor,
+    /// Row by row. Every cell is stepped on its own.
+    RowMajor,
+}
+
+/// Cells of a `width` x `height` board packed one bit per cell, column by
+/// column: row `y` of column `x` is bit `y % 64` of the column's word
+/// `y / 64`. Bits past the last row stay clear, so whole columns can be
+/// compared, counted and shifted a word at a time. A `RowMajor` grid packs
+/// rows the same way instead, with columns and rows swapped throughout.
+/// Every write goes through the grid, which keeps count of the live cells
+/// as they change.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct BitGrid {
+    width: usize,
+    height: usize,
+    layout: Layout,
+    // words per column, or per row in a row-major grid
+    words_per_line: usize,
+    words: Vec<u64>,
+    live: u32,
+}
+
+fn count_ones(words: &[u64]) -> u32 {
+    words.iter().map(|word| word.count_ones()).sum()
+}
+
+// positions of the set bits of `word`, lowest first
+fn set_bits(mut word: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if word == 0 {
+            return None;
+        }
+        let bit = word.trailing_zeros() as usize;
+        word &= word - 1;
+        Some(bit)
+    })
+}
+
+impl BitGrid {
+    /// All dead, column-major.
+    pub(crate) fn new(width: usize, height: usize) -> BitGrid {
+        BitGrid::with_layout(width, height, Layout::ColumnMajor)
+    }
+    /// All dead.
+    pub(crate) fn with_layout(width: usize, height: usize, layout: Layout) -> BitGrid {
+        let (lines, line_length) = match layout {
+            Layout::ColumnMajor => (width, height),
+            Layout::RowMajor => (height, width),
+        };
+        let words_per_line = line_length.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            layout,
+            words_per_line,
+            words: vec![0; lines * words_per_line],
+            live: 0,
+        }
+    }
+    /// Copy of `state[x][y]` columns, which must all have `height` cells.
+    pub(crate) fn from_columns(state: &[Vec<bool>], height: usize) -> BitGrid {
+        let mut grid = BitGrid::new(state.len(), height);
+        for (x, col) in state.iter().enumerate() {
+            for (y, &alive) in col.iter().enumerate() {
+                grid.set(x, y, alive);
+            }
+        }
+        grid
+    }
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+    pub(crate) fn height(&self) -> usize {
+        self.height
+    }
+    pub(crate) fn layout(&self) -> Layout {
+        self.layout
+    }
+    /// Copy with the same cells laid out as `layout`.
+    pub(crate) fn relaid(&self, layout: Layout) -> BitGrid {
+        let mut grid = BitGrid::with_layout(self.width, self.height, layout);
+        for (x, y) in self.live_cells() {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+    // word holding (x, y) and the bit of the cell within it
+    fn position(&self, x: usize, y: usize) -> (usize, usize) {
+        match self.layout {
+            Layout::ColumnMajor => (x * self.words_per_line + y / 64, y % 64),
+            Layout::RowMajor => (y * self.words_per_line + x / 64, x % 64),
+        }
+    }
+    /// Whether the grid has no cells, as with `BitGrid::default()`.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    pub(crate) fn get(&self, x: usize, y: usize) -> bool {
+        let (index, bit) = self.position(x, y);
+        self.words[index] >> bit & 1 != 0
+    }
+    pub(crate) fn set(&mut self, x: usize, y: usize, alive: bool) {
+        let (index, bit) = self.position(x, y);
+        let word = &mut self.words[index];
+        let was_alive = *word >> bit & 1 != 0;
+        if alive {
+            *word |= 1 << bit;
+        } else {
+            *word &= !(1 << bit);
+        }
+        match (was_alive, alive) {
+            (false, true) => self.live += 1,
+            (true, false) => self.live -= 1,
+            _ => {}
+        }
+    }
+    /// Makes this a copy of `other`, reusing the words when the sizes match.
+    #[cfg_attr(not(feature = "render"), allow(dead_code))]
+    pub(crate) fn copy_from(&mut self, other: &BitGrid) {
+        if self.width == other.width && self.height == other.height && self.layout == other.layout {
+            self.words.copy_from_slice(&other.words);
+            self.live = other.live;
+        } else {
+            *self = other.clone();
+        }
+    }
+    /// Kills every cell, keeping the size.
+    pub(crate) fn clear(&mut self) {
+        for word in &mut self.words {
+            *word = 0;
+        }
+        self.live = 0;
+    }
+    // words of column `x`, or of row `x` in a row-major grid
+    fn line(&self, x: usize) -> &[u64] {
+        &self.words[x * self.words_per_line..(x + 1) * self.words_per_line]
+    }
+    /// Overwrites column `x` with the same column of `other`, a grid of the
+    /// same size and layout.
+    pub(crate) fn copy_column(&mut self, other: &BitGrid, x: usize) {
+        if self.layout == Layout::RowMajor {
+            for y in 0..self.height {
+                self.set(x, y, other.get(x, y));
+            }
+            return;
+        }
+        let range = x * self.words_per_line..(x + 1) * self.words_per_line;
+        self.live = self.live - count_ones(&self.words[range.clone()]) + count_ones(&other.words[range.clone()]);
+        self.words[range.clone()].copy_from_slice(&other.words[range]);
+    }
+    /// Live cells, kept up to date rather than counted.
+    pub(crate) fn count_live(&self) -> u32 {
+        self.live
+    }
+    // (x, y) of bit `bit` of word `index`
+    fn cell(&self, index: usize, bit: usize) -> (usize, usize) {
+        let (line, offset) = (index / self.words_per_line, index % self.words_per_line * 64 + bit);
+        match self.layout {
+            Layout::ColumnMajor => (line, offset),
+            Layout::RowMajor => (offset, line),
+        }
+    }
+    /// `(x, y)` of every live cell, column by column (row by row in a
+    /// row-major grid).
+    pub(crate) fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let lines = self.words.len().checked_div(self.words_per_line).unwrap_or(0);
+        (0..lines).flat_map(move |line| {
+            self.line(line).iter().enumerate().flat_map(move |(index, &word)| {
+                set_bits(word).map(move |bit| self.cell(line * self.words_per_line + index, bit))
+            })
+        })
+    }
+    /// `(x, y, alive)` of every cell that differs in `other`, a grid of the
+    /// same size and layout, with `alive` its value there, in the order of
+    /// `live_cells`.
+    pub(crate) fn changed_cells<'a>(&'a self, other: &'a BitGrid) -> impl Iterator<Item = (usize, usize, bool)> + 'a {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .enumerate()
+            .flat_map(move |(index, (&before, &after))| {
+                set_bits(before ^ after).map(move |bit| {
+                    let (x, y) = self.cell(index, bit);
+                    (x, y, after >> bit & 1 != 0)
+                })
+            })
+    }
+    /// Copy at `width` x `height` with the cells that still fit where they
+    /// are and new space dead, in the same layout.
+    pub(crate) fn resized(&self, width: usize, height: usize) -> BitGrid {
+        let mut grid = BitGrid::with_layout(width, height, self.layout);
+        for (x, y) in self.live_cells() {
+            if x < width && y < height {
+                grid.set(x, y, true);
+            }
+        }
+        grid
+    }
+    /// Column `x` of the next generation under a radius 1 totalistic rule
+    /// with dead edges, where `birth[n]` and `survival[n]` say what a dead
+    /// or live cell with `n` live neighbours does. Each word of the column
+    /// is worked out 64 cells at once: the eight neighbours are added up as
+    /// bit planes into a 4 bit count per cell. Only for column-major grids.
+    /// The column is written to column `x` of `next`, a grid of the same
+    /// size, so stepping allocates nothing.
+    pub(crate) fn next_column(&self, x: usize, birth: &[bool; 9], survival: &[bool; 9], next: &mut BitGrid) {
+        let words_per_col = self.words_per_line;
+        let range = x * words_per_col..(x + 1) * words_per_col;
+        next.live -= count_ones(&next.words[range.clone()]);
+        let next_live = &mut next.live;
+        let next = &mut next.words[range];
+        let word = |x: Option<usize>, index: usize| match x {
+            Some(x) if x < self.width && index < words_per_col => self.words[x * words_per_col + index],
+            _ => 0,
+        };
+        for (index, next) in next.iter_mut().enumerate() {
+            *next = 0;
+            let (mut ones, mut twos, mut fours, mut eights) = (0u64, 0u64, 0u64, 0u64);
+            for &col in &[x.checked_sub(1), Some(x), Some(x + 1)] {
+                let middle = word(col, index);
+                let above = index.checked_sub(1).map_or(0, |above| word(col, above));
+                let below = word(col, index + 1);
+                // the cell one row up and one row down from each bit
+                let planes = [middle << 1 | above >> 63, middle >> 1 | below << 63, middle];
+                let planes = if col == Some(x) { &planes[..2] } else { &planes[..] };
+                for &plane in planes {
+                    let carry = ones & plane;
+                    ones ^= plane;
+                    let carry_twos = twos & carry;
+                    twos ^= carry;
+                    let carry_fours = fours & carry_twos;
+                    fours ^= carry_twos;
+                    eights |= carry_fours;
+                }
+            }
+            let alive = word(Some(x), index);
+            let bit = |plane: u64, set: bool| if set { plane } else { !plane };
+            for count in 0..9 {
+                let outcome = (if survival[count] { alive } else { 0 }) | (if birth[count] { !alive } else { 0 });
+                *next |= outcome
+                    & bit(ones, count & 1 != 0)
+                    & bit(twos, count & 2 != 0)
+                    & bit(fours, count & 4 != 0)
+                    & bit(eights, count & 8 != 0);
+            }
+        }
+        if !self.height.is_multiple_of(64) {
+            if let Some(last) = next.last_mut() {
+                *last &= (1 << (self.height % 64)) - 1;
+            }
+        }
+        *next_live += count_ones(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn random_grid(width: usize, height: usize, layout: Layout, seed: u64) -> BitGrid {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut grid = BitGrid::with_layout(width, height, layout);
+        for x in 0..width {
+            for y in 0..height {
+                grid.set(x, y, rng.gen_bool(0.4));
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn layouts_hold_the_same_cells() {
+        for &(width, height) in &[(1, 1), (7, 65), (130, 3), (70, 70)] {
+            let columns = random_grid(width, height, Layout::ColumnMajor, width as u64);
+            let rows = columns.relaid(Layout::RowMajor);
+            assert_eq!(rows.count_live(), columns.count_live());
+            let mut cells: Vec<_> = rows.live_cells().collect();
+            cells.sort_unstable();
+            assert_eq!(cells, columns.live_cells().collect::<Vec<_>>());
+            assert_eq!(rows.relaid(Layout::ColumnMajor), columns);
+
+            let next_columns = random_grid(width, height, Layout::ColumnMajor, height as u64);
+            let next_rows = next_columns.relaid(Layout::RowMajor);
+            let mut changes: Vec<_> = rows.changed_cells(&next_rows).collect();
+            changes.sort_unstable();
+            assert_eq!(changes, columns.changed_cells(&next_columns).collect::<Vec<_>>());
+        }
+    }
+}
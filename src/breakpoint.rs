@@ -0,0 +1,42 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+#[derive(Debug, Default)]
+pub(crate) struct Breakpoint {
+    generation: Option<u64>,
+    callback: Option<js_sys::Function>,
+}
+
+impl Game {
+    // called once per committed generation
+    pub(crate) fn check_breakpoint(&mut self) {
+        if self.breakpoint.generation != Some(self.generation) {
+            return;
+        }
+        self.breakpoint.generation = None;
+        self.running = false;
+        if let Some(callback) = self.breakpoint.callback.take() {
+            // a throwing listener must not stop the simulation
+            if let Err(error) = callback.call1(&JsValue::NULL, &JsValue::from(self.generation as f64)) {
+                self.diagnostic(&format!("breakpoint callback threw: {:?}", error));
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Pauses the game once generation `generation` is committed, leaving
+    /// the board exactly at it, and then calls `callback(generation)` if
+    /// given. The breakpoint is cleared when hit; `resume` continues.
+    pub fn set_breakpoint(&mut self, generation: u64, callback: Option<js_sys::Function>) {
+        self.breakpoint = Breakpoint {
+            generation: Some(generation),
+            callback,
+        };
+    }
+    pub fn clear_breakpoint(&mut self) {
+        self.breakpoint = Breakpoint::default();
+    }
+}
@@ -0,0 +1,98 @@
+use std::f64::consts::PI;
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Opt-in per-generation centroid of the live cells, for following a
+/// drifting pattern.
+#[derive(Debug, Default)]
+pub(crate) struct CentroidTracker {
+    pub(crate) enabled: bool,
+    // centroid after the last committed generation, `None` on an empty or
+    // just replaced board
+    last: Option<(f64, f64)>,
+    velocity: (f64, f64),
+}
+
+impl CentroidTracker {
+    pub(crate) fn reset(&mut self) {
+        self.last = None;
+        self.velocity = (0.0, 0.0);
+    }
+}
+
+// shortest signed step from `from` to `to` along an axis of `size` cells
+// that wraps, so crossing the seam reads as a step of a cell or two
+fn wrapped_delta(from: f64, to: f64, size: f64) -> f64 {
+    let delta = to - from;
+    if delta > size / 2.0 {
+        delta - size
+    } else if delta < -size / 2.0 {
+        delta + size
+    } else {
+        delta
+    }
+}
+
+impl Game {
+    // Average position of the live cells taking each axis as a circle, so a
+    // pattern straddling the seam averages to where its cells are rather
+    // than to the middle of the board. For a compact pattern away from the
+    // seam it is close to `center_of_mass`.
+    fn circular_centroid(&self) -> Option<(f64, f64)> {
+        let (width, height) = (self.grid.width as f64, self.grid.height as f64);
+        let (x_step, y_step) = (2.0 * PI / width, 2.0 * PI / height);
+        let (mut x, mut y, mut count) = ((0.0, 0.0), (0.0, 0.0), 0);
+        for (col, row) in self.iter_live() {
+            let (col, row) = (col as f64 * x_step, row as f64 * y_step);
+            x = (x.0 + col.cos(), x.1 + col.sin());
+            y = (y.0 + row.cos(), y.1 + row.sin());
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        let position = |(cos, sin): (f64, f64), step: f64, size: f64| (sin.atan2(cos) / step).rem_euclid(size);
+        Some((position(x, x_step, width), position(y, y_step, height)))
+    }
+    // called once per committed generation
+    pub(crate) fn track_centroid_step(&mut self) {
+        if !self.centroid.enabled {
+            return;
+        }
+        let (width, height) = (self.grid.width as f64, self.grid.height as f64);
+        let current = self.circular_centroid();
+        self.centroid.velocity = match (self.centroid.last, current) {
+            (Some((x0, y0)), Some((x1, y1))) => (wrapped_delta(x0, x1, width), wrapped_delta(y0, y1, height)),
+            _ => (0.0, 0.0),
+        };
+        self.centroid.last = current;
+    }
+    /// Centroid movement, in cells, over the last committed generation;
+    /// `(0, 0)` while tracking is off, on an empty board and for the first
+    /// generation after tracking starts or the board is replaced.
+    pub fn tracked_velocity(&self) -> (f64, f64) {
+        self.centroid.velocity
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Follows the centroid of the live cells from one generation to the
+    /// next, for panning a view after a drifting pattern. Movement across
+    /// the seam is taken the short way round, so it doesn't read as a jump
+    /// of a board width.
+    pub fn track_centroid(&mut self, enabled: bool) {
+        self.centroid.enabled = enabled;
+        self.centroid.reset();
+        if enabled {
+            self.centroid.last = self.circular_centroid();
+        }
+    }
+    /// `tracked_velocity` for JS, as `[dx, dy]`.
+    #[wasm_bindgen(js_name = tracked_velocity)]
+    pub fn tracked_velocity_js(&self) -> Vec<f64> {
+        let (dx, dy) = self.tracked_velocity();
+        vec![dx, dy]
+    }
+}
@@ -0,0 +1,42 @@
+/// `#rrggbb` for a hue in degrees and a saturation and lightness in `0..=1`.
+pub(crate) fn hsl_to_hex(hue: f64, saturation: f64, lightness: f64) -> String {
+    let (red, green, blue) = hsl_to_rgb(hue, saturation, lightness);
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+/// `hsl_to_hex` as red, green and blue bytes.
+pub(crate) fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (red, green, blue) = match sector as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let lift = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + lift) * 255.0).round() as u8;
+    (channel(red), channel(green), channel(blue))
+}
+
+/// Red, green and blue bytes of a `#rrggbb` or `#rgb` color, or `None` for
+/// anything else.
+pub(crate) fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let color = color.trim();
+    if !color.starts_with('#') || !color.is_ascii() {
+        return None;
+    }
+    let digits = &color[1..];
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match digits.len() {
+        6 => Some((channel(&digits[0..2])?, channel(&digits[2..4])?, channel(&digits[4..6])?)),
+        3 => {
+            let short = |index: usize| channel(&digits[index..=index]).map(|value| value * 17);
+            Some((short(0)?, short(1)?, short(2)?))
+        }
+        _ => None,
+    }
+}
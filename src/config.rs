@@ -0,0 +1,637 @@
+use std::ops::RangeInclusive;
+use wasm_bindgen::prelude::*;
+
+use crate::edges::EdgeMode;
+use crate::edit::{EditPolicy, OutOfBounds};
+use crate::game::{BitOrder, Game, UpdateMode};
+use crate::garden::Garden;
+use crate::homeostasis::Homeostasis;
+use crate::pattern::FitPolicy;
+use crate::region::Rect;
+use crate::rule::{Rule, MAX_RADIUS};
+
+/// Every setting of a game, without the board: the cells, walls (and
+/// their polarity, which rewrites the wall cells), generation count and
+/// random generator stay out, so a config can be moved between boards of
+/// any size and applying one leaves the cells alone. Taken with
+/// `Game::config` and applied with `Game::set_config`, or as JSON through
+/// `export_config` and `apply_config`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) rule: Rule,
+    pub(crate) rule_regions: Vec<(Rect, Rule)>,
+    pub(crate) active_region: Option<Rect>,
+    pub(crate) kernel: Option<Vec<u32>>,
+    pub(crate) birth_probability: f64,
+    pub(crate) survival_probability: f64,
+    pub(crate) update_mode: UpdateMode,
+    pub(crate) edge_mode: EdgeMode,
+    pub(crate) edit_policy: EditPolicy,
+    pub(crate) out_of_bounds: OutOfBounds,
+    pub(crate) bit_order: BitOrder,
+    pub(crate) fit_policy: FitPolicy,
+    pub(crate) target_fps: f64,
+    pub(crate) background_interval_ms: f64,
+    pub(crate) generation_duration_ms: f64,
+    pub(crate) warmup_generations: u32,
+    pub(crate) garden: (u32, i16, f64),
+    pub(crate) population_target: (u32, f64),
+    pub(crate) max_period: u32,
+    pub(crate) neighbour_cache: bool,
+    pub(crate) spaceship_tracking: bool,
+    pub(crate) centroid_tracking: bool,
+    pub(crate) console_logging: bool,
+    pub(crate) hue_cycle: f64,
+    pub(crate) color_components: bool,
+    pub(crate) show_ghost: bool,
+    pub(crate) birth_flash: Option<String>,
+    pub(crate) transparent_background: bool,
+    pub(crate) background_color: String,
+    pub(crate) motion_blur: f64,
+    pub(crate) show_border: bool,
+    pub(crate) border_color: String,
+    pub(crate) wall_color: String,
+    pub(crate) cell_size: i16,
+    pub(crate) density_block: i16,
+    pub(crate) seam_margin: i16,
+    pub(crate) pixel_ratio: f64,
+    pub(crate) fit_canvas: Option<(f64, f64)>,
+}
+
+fn update_mode_name(mode: UpdateMode) -> &'static str {
+    match mode {
+        UpdateMode::Synchronous => "synchronous",
+        UpdateMode::Sequential => "sequential",
+    }
+}
+
+fn edge_mode_name(mode: EdgeMode) -> &'static str {
+    match mode {
+        EdgeMode::Dead => "dead",
+        EdgeMode::Wrap => "wrap",
+        EdgeMode::Mirror => "mirror",
+    }
+}
+
+fn edit_policy_name(policy: EditPolicy) -> &'static str {
+    match policy {
+        EditPolicy::Queue => "queue",
+        EditPolicy::Restart => "restart",
+    }
+}
+
+fn out_of_bounds_name(policy: OutOfBounds) -> &'static str {
+    match policy {
+        OutOfBounds::Ignore => "ignore",
+        OutOfBounds::Wrap => "wrap",
+        OutOfBounds::Error => "error",
+    }
+}
+
+fn bit_order_name(order: BitOrder) -> &'static str {
+    match order {
+        BitOrder::LsbFirst => "lsb-first",
+        BitOrder::MsbFirst => "msb-first",
+    }
+}
+
+fn fit_policy_name(policy: FitPolicy) -> &'static str {
+    match policy {
+        FitPolicy::Clip => "clip",
+        FitPolicy::GrowGrid => "grow-grid",
+    }
+}
+
+fn set(object: &js_sys::Object, key: &str, value: &JsValue) -> Result<(), JsValue> {
+    js_sys::Reflect::set(object, &key.into(), value)?;
+    Ok(())
+}
+
+fn ranges_to_json(ranges: &[RangeInclusive<u32>]) -> JsValue {
+    ranges
+        .iter()
+        .map(|range| -> JsValue {
+            [*range.start(), *range.end()].iter().map(|&bound| JsValue::from(bound)).collect::<js_sys::Array>().into()
+        })
+        .collect::<js_sys::Array>()
+        .into()
+}
+
+/// Reads the keys present in a parsed config object; missing keys stay as
+/// they were and a key of the wrong type is an error.
+struct Fields<'a> {
+    object: &'a JsValue,
+}
+
+impl<'a> Fields<'a> {
+    fn get(&self, key: &str) -> Result<Option<JsValue>, JsValue> {
+        let value = js_sys::Reflect::get(self.object, &key.into())?;
+        Ok(if value.is_undefined() { None } else { Some(value) })
+    }
+    fn number(&self, key: &str, target: &mut f64) -> Result<(), JsValue> {
+        if let Some(value) = self.get(key)? {
+            *target = value.as_f64().ok_or_else(|| format!("config key '{}' must be a number", key))?;
+        }
+        Ok(())
+    }
+    fn bool(&self, key: &str, target: &mut bool) -> Result<(), JsValue> {
+        if let Some(value) = self.get(key)? {
+            *target = value.as_bool().ok_or_else(|| format!("config key '{}' must be a boolean", key))?;
+        }
+        Ok(())
+    }
+    fn string(&self, key: &str, target: &mut String) -> Result<(), JsValue> {
+        if let Some(value) = self.get(key)? {
+            *target = value.as_string().ok_or_else(|| format!("config key '{}' must be a string", key))?;
+        }
+        Ok(())
+    }
+    // one of `names`, stored as the matching value
+    fn name<T: Copy>(&self, key: &str, target: &mut T, names: &[(&str, T)]) -> Result<(), JsValue> {
+        let mut name = String::new();
+        if self.get(key)?.is_none() {
+            return Ok(());
+        }
+        self.string(key, &mut name)?;
+        *target = names
+            .iter()
+            .find(|(candidate, _)| *candidate == name)
+            .map(|&(_, value)| value)
+            .ok_or_else(|| format!("unknown {} '{}'", key.replace('_', " "), name))?;
+        Ok(())
+    }
+    fn numbers(&self, key: &str) -> Result<Option<Vec<f64>>, JsValue> {
+        let value = match self.get(key)? {
+            Some(value) if !value.is_null() => value,
+            _ => return Ok(None),
+        };
+        if !js_sys::Array::is_array(&value) {
+            return Err(format!("config key '{}' must be an array", key).into());
+        }
+        let mut numbers = vec![];
+        for item in js_sys::Array::from(&value).iter() {
+            numbers.push(item.as_f64().ok_or_else(|| format!("config key '{}' must hold numbers", key))?);
+        }
+        Ok(Some(numbers))
+    }
+    fn object(&self, key: &str) -> Result<Option<JsValue>, JsValue> {
+        match self.get(key)? {
+            Some(value) if value.is_object() => Ok(Some(value)),
+            Some(_) => Err(format!("config key '{}' must be an object", key).into()),
+            None => Ok(None),
+        }
+    }
+    fn ranges(&self, key: &str, target: &mut Vec<RangeInclusive<u32>>) -> Result<(), JsValue> {
+        let value = match self.get(key)? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        let error = || format!("config key '{}' must hold [start, end] pairs", key);
+        if !js_sys::Array::is_array(&value) {
+            return Err(error().into());
+        }
+        let mut ranges = vec![];
+        for pair in js_sys::Array::from(&value).iter() {
+            if !js_sys::Array::is_array(&pair) || js_sys::Array::from(&pair).length() != 2 {
+                return Err(error().into());
+            }
+            let pair = js_sys::Array::from(&pair);
+            let start = pair.get(0).as_f64().filter(|&start| start >= 0.0).ok_or_else(error)?;
+            let end = pair.get(1).as_f64().filter(|&end| end >= 0.0).ok_or_else(error)?;
+            ranges.push(start as u32..=end as u32);
+        }
+        *target = ranges;
+        Ok(())
+    }
+}
+
+// every range must be ordered and within the counts a cell of `radius` can see
+fn check_ranges(key: &str, ranges: &[RangeInclusive<u32>], radius: i16) -> Result<(), JsValue> {
+    let max = (2 * radius as u32 + 1).pow(2) - 1;
+    match ranges.iter().find(|range| range.start() > range.end() || *range.end() > max) {
+        Some(range) => Err(format!("invalid {} range [{}, {}], counts go from 0 to {}", key, range.start(), range.end(), max).into()),
+        None => Ok(()),
+    }
+}
+
+impl Config {
+    fn to_js(&self) -> Result<JsValue, JsValue> {
+        let config = js_sys::Object::new();
+        let rule = js_sys::Object::new();
+        set(&rule, "radius", &JsValue::from(self.rule.radius))?;
+        // an isotropic rule is all in its notation
+        match &self.rule.isotropic {
+            Some(table) => set(&rule, "isotropic", &table.notation.as_str().into())?,
+            None => {
+                set(&rule, "birth", &ranges_to_json(&self.rule.birth))?;
+                set(&rule, "survival", &ranges_to_json(&self.rule.survival))?;
+                set(&rule, "isotropic", &JsValue::NULL)?;
+            }
+        }
+        set(&rule, "states", &JsValue::from(self.rule.states))?;
+        set(&config, "rule", &rule)?;
+        let rule_regions = js_sys::Array::new();
+        for (rect, rule) in &self.rule_regions {
+            let region = js_sys::Object::new();
+            set(&region, "x", &rect.x.into())?;
+            set(&region, "y", &rect.y.into())?;
+            set(&region, "width", &rect.width.into())?;
+            set(&region, "height", &rect.height.into())?;
+            set(&region, "birth", &ranges_to_json(&rule.birth))?;
+            set(&region, "survival", &ranges_to_json(&rule.survival))?;
+            rule_regions.push(&region);
+        }
+        set(&config, "rule_regions", &rule_regions)?;
+        let active_region = self.active_region.map(|rect| -> JsValue {
+            [rect.x, rect.y, rect.width, rect.height].iter().map(|&bound| JsValue::from(bound)).collect::<js_sys::Array>().into()
+        });
+        set(&config, "active_region", &active_region.unwrap_or(JsValue::NULL))?;
+        let kernel = self.kernel.as_ref().map(|weights| weights.iter().map(|&weight| JsValue::from(weight)).collect::<js_sys::Array>());
+        set(&config, "kernel", &kernel.map_or(JsValue::NULL, JsValue::from))?;
+        set(&config, "birth_probability", &self.birth_probability.into())?;
+        set(&config, "survival_probability", &self.survival_probability.into())?;
+
+        set(&config, "update_mode", &update_mode_name(self.update_mode).into())?;
+        set(&config, "edge_mode", &edge_mode_name(self.edge_mode).into())?;
+        set(&config, "edit_policy", &edit_policy_name(self.edit_policy).into())?;
+        set(&config, "out_of_bounds", &out_of_bounds_name(self.out_of_bounds).into())?;
+        set(&config, "bit_order", &bit_order_name(self.bit_order).into())?;
+        set(&config, "fit_policy", &fit_policy_name(self.fit_policy).into())?;
+        set(&config, "target_fps", &self.target_fps.into())?;
+        set(&config, "background_interval_ms", &self.background_interval_ms.into())?;
+        set(&config, "generation_duration_ms", &self.generation_duration_ms.into())?;
+        set(&config, "warmup_generations", &self.warmup_generations.into())?;
+        let garden = js_sys::Object::new();
+        set(&garden, "period", &self.garden.0.into())?;
+        set(&garden, "depth", &self.garden.1.into())?;
+        set(&garden, "density", &self.garden.2.into())?;
+        set(&config, "garden", &garden)?;
+        let population_target = js_sys::Object::new();
+        set(&population_target, "target", &self.population_target.0.into())?;
+        set(&population_target, "gain", &self.population_target.1.into())?;
+        set(&config, "population_target", &population_target)?;
+        set(&config, "max_period", &self.max_period.into())?;
+        set(&config, "neighbour_cache", &self.neighbour_cache.into())?;
+        set(&config, "spaceship_tracking", &self.spaceship_tracking.into())?;
+        set(&config, "centroid_tracking", &self.centroid_tracking.into())?;
+        set(&config, "console_logging", &self.console_logging.into())?;
+
+        set(&config, "hue_cycle", &self.hue_cycle.into())?;
+        set(&config, "color_components", &self.color_components.into())?;
+        set(&config, "show_ghost", &self.show_ghost.into())?;
+        set(&config, "birth_flash", &self.birth_flash.as_ref().map_or(JsValue::NULL, |color| color.as_str().into()))?;
+        set(&config, "transparent_background", &self.transparent_background.into())?;
+        set(&config, "background_color", &self.background_color.as_str().into())?;
+        set(&config, "motion_blur", &self.motion_blur.into())?;
+        set(&config, "show_border", &self.show_border.into())?;
+        set(&config, "border_color", &self.border_color.as_str().into())?;
+        set(&config, "wall_color", &self.wall_color.as_str().into())?;
+        set(&config, "cell_size", &self.cell_size.into())?;
+        set(&config, "density_block", &self.density_block.into())?;
+        set(&config, "seam_margin", &self.seam_margin.into())?;
+        set(&config, "pixel_ratio", &self.pixel_ratio.into())?;
+        let fit_canvas = self.fit_canvas.map(|(width, height)| -> JsValue {
+            [width, height].iter().map(|&side| JsValue::from(side)).collect::<js_sys::Array>().into()
+        });
+        set(&config, "fit_canvas", &fit_canvas.unwrap_or(JsValue::NULL))?;
+        Ok(config.into())
+    }
+    // overwrites the settings the parsed JSON has, checking them all
+    // before anything changes
+    fn read(&mut self, object: &JsValue) -> Result<(), JsValue> {
+        if !object.is_object() {
+            return Err("config must be a JSON object".into());
+        }
+        let fields = Fields { object };
+        if let Some(rule) = fields.object("rule")? {
+            let rule_fields = Fields { object: &rule };
+            let mut radius = self.rule.radius as f64;
+            let mut states = self.rule.states as f64;
+            rule_fields.number("radius", &mut radius)?;
+            rule_fields.number("states", &mut states)?;
+            if !(1.0..=MAX_RADIUS as f64).contains(&radius) {
+                return Err(format!("invalid rule radius {}, expected 1 to {}", radius, MAX_RADIUS).into());
+            }
+            self.rule.radius = radius as i16;
+            self.rule.states = (states as u8).max(2);
+            match rule_fields.get("isotropic")? {
+                Some(notation) if !notation.is_null() => {
+                    let notation = notation.as_string().ok_or("config key 'isotropic' must be a string")?;
+                    let parsed = Rule::parse_isotropic(&notation)?;
+                    self.rule.birth = parsed.birth;
+                    self.rule.survival = parsed.survival;
+                    self.rule.isotropic = parsed.isotropic;
+                }
+                Some(_) => self.rule.isotropic = None,
+                None => {}
+            }
+            let has_ranges = rule_fields.get("birth")?.is_some() || rule_fields.get("survival")?.is_some();
+            if has_ranges && self.rule.isotropic.is_some() {
+                return Err("birth and survival ranges can't be combined with an isotropic rule, its notation holds them".into());
+            }
+            rule_fields.ranges("birth", &mut self.rule.birth)?;
+            rule_fields.ranges("survival", &mut self.rule.survival)?;
+            check_ranges("birth", &self.rule.birth, self.rule.radius)?;
+            check_ranges("survival", &self.rule.survival, self.rule.radius)?;
+        }
+        if let Some(value) = fields.get("rule_regions")? {
+            if !js_sys::Array::is_array(&value) {
+                return Err("config key 'rule_regions' must be an array".into());
+            }
+            let mut rule_regions = vec![];
+            for region in js_sys::Array::from(&value).iter() {
+                if !region.is_object() {
+                    return Err("config key 'rule_regions' must hold objects".into());
+                }
+                let region_fields = Fields { object: &region };
+                let mut bounds = [0.0; 4];
+                for (key, bound) in ["x", "y", "width", "height"].iter().zip(bounds.iter_mut()) {
+                    region_fields.get(key)?.ok_or_else(|| format!("rule region is missing '{}'", key))?;
+                    region_fields.number(key, bound)?;
+                }
+                let mut rule = Rule::default();
+                region_fields.ranges("birth", &mut rule.birth)?;
+                region_fields.ranges("survival", &mut rule.survival)?;
+                check_ranges("birth", &rule.birth, 1)?;
+                check_ranges("survival", &rule.survival, 1)?;
+                let [x, y, width, height] = bounds;
+                rule_regions.push((Rect { x: x as i16, y: y as i16, width: width as i16, height: height as i16 }, rule));
+            }
+            self.rule_regions = rule_regions;
+        }
+        if fields.get("active_region")?.is_some() {
+            self.active_region = match fields.numbers("active_region")? {
+                Some(bounds) if bounds.len() == 4 => {
+                    Some(Rect { x: bounds[0] as i16, y: bounds[1] as i16, width: bounds[2] as i16, height: bounds[3] as i16 })
+                }
+                Some(_) => return Err("config key 'active_region' must be [x, y, width, height]".into()),
+                None => None,
+            };
+        }
+        if fields.get("kernel")?.is_some() {
+            self.kernel = fields.numbers("kernel")?.map(|weights| weights.iter().map(|&weight| weight as u32).collect());
+            if let Some(weights) = &self.kernel {
+                let side = (weights.len() as f64).sqrt().round() as usize;
+                if side * side != weights.len() || side.is_multiple_of(2) {
+                    return Err(format!("kernel must be an odd sized square, got {} weights", weights.len()).into());
+                }
+            }
+        }
+        fields.number("birth_probability", &mut self.birth_probability)?;
+        fields.number("survival_probability", &mut self.survival_probability)?;
+
+        fields.name("update_mode", &mut self.update_mode, &[
+            ("synchronous", UpdateMode::Synchronous),
+            ("sequential", UpdateMode::Sequential),
+        ])?;
+        fields.name("edge_mode", &mut self.edge_mode, &[
+            ("dead", EdgeMode::Dead),
+            ("wrap", EdgeMode::Wrap),
+            ("mirror", EdgeMode::Mirror),
+        ])?;
+        fields.name("edit_policy", &mut self.edit_policy, &[
+            ("queue", EditPolicy::Queue),
+            ("restart", EditPolicy::Restart),
+        ])?;
+        fields.name("out_of_bounds", &mut self.out_of_bounds, &[
+            ("ignore", OutOfBounds::Ignore),
+            ("wrap", OutOfBounds::Wrap),
+            ("error", OutOfBounds::Error),
+        ])?;
+        fields.name("bit_order", &mut self.bit_order, &[
+            ("lsb-first", BitOrder::LsbFirst),
+            ("msb-first", BitOrder::MsbFirst),
+        ])?;
+        fields.name("fit_policy", &mut self.fit_policy, &[
+            ("clip", FitPolicy::Clip),
+            ("grow-grid", FitPolicy::GrowGrid),
+        ])?;
+        fields.number("target_fps", &mut self.target_fps)?;
+        fields.number("background_interval_ms", &mut self.background_interval_ms)?;
+        fields.number("generation_duration_ms", &mut self.generation_duration_ms)?;
+        let mut warmup_generations = self.warmup_generations as f64;
+        fields.number("warmup_generations", &mut warmup_generations)?;
+        self.warmup_generations = warmup_generations as u32;
+        if let Some(garden) = fields.object("garden")? {
+            let garden_fields = Fields { object: &garden };
+            let (mut period, mut depth) = (self.garden.0 as f64, self.garden.1 as f64);
+            garden_fields.number("period", &mut period)?;
+            garden_fields.number("depth", &mut depth)?;
+            garden_fields.number("density", &mut self.garden.2)?;
+            self.garden.0 = period as u32;
+            self.garden.1 = depth as i16;
+        }
+        if let Some(population_target) = fields.object("population_target")? {
+            let target_fields = Fields { object: &population_target };
+            let mut target = self.population_target.0 as f64;
+            target_fields.number("target", &mut target)?;
+            target_fields.number("gain", &mut self.population_target.1)?;
+            self.population_target.0 = target as u32;
+        }
+        let mut max_period = self.max_period as f64;
+        fields.number("max_period", &mut max_period)?;
+        self.max_period = max_period as u32;
+        fields.bool("neighbour_cache", &mut self.neighbour_cache)?;
+        fields.bool("spaceship_tracking", &mut self.spaceship_tracking)?;
+        fields.bool("centroid_tracking", &mut self.centroid_tracking)?;
+        fields.bool("console_logging", &mut self.console_logging)?;
+
+        fields.number("hue_cycle", &mut self.hue_cycle)?;
+        fields.bool("color_components", &mut self.color_components)?;
+        fields.bool("show_ghost", &mut self.show_ghost)?;
+        match fields.get("birth_flash")? {
+            Some(color) if !color.is_null() => {
+                self.birth_flash = Some(color.as_string().ok_or("config key 'birth_flash' must be a string")?);
+            }
+            Some(_) => self.birth_flash = None,
+            None => {}
+        }
+        fields.bool("transparent_background", &mut self.transparent_background)?;
+        fields.string("background_color", &mut self.background_color)?;
+        fields.number("motion_blur", &mut self.motion_blur)?;
+        fields.bool("show_border", &mut self.show_border)?;
+        fields.string("border_color", &mut self.border_color)?;
+        fields.string("wall_color", &mut self.wall_color)?;
+        let (mut density_block, mut seam_margin) = (self.density_block as f64, self.seam_margin as f64);
+        let mut cell_size = self.cell_size as f64;
+        fields.number("density_block", &mut density_block)?;
+        fields.number("seam_margin", &mut seam_margin)?;
+        fields.number("cell_size", &mut cell_size)?;
+        self.density_block = density_block as i16;
+        self.seam_margin = seam_margin as i16;
+        self.cell_size = cell_size as i16;
+        fields.number("pixel_ratio", &mut self.pixel_ratio)?;
+        if fields.get("fit_canvas")?.is_some() {
+            self.fit_canvas = match fields.numbers("fit_canvas")? {
+                Some(sides) if sides.len() == 2 => Some((sides[0], sides[1])),
+                Some(_) => return Err("config key 'fit_canvas' must be [width, height]".into()),
+                None => None,
+            };
+        }
+        Ok(())
+    }
+}
+
+impl Game {
+    /// Snapshot of every setting, see `Config`.
+    pub fn config(&self) -> Config {
+        let Garden { period, depth, density } = self.garden;
+        let Homeostasis { target, gain } = self.homeostasis;
+        Config {
+            rule: self.rule.clone(),
+            rule_regions: self.rule_regions.clone(),
+            active_region: self.active_region,
+            kernel: self.kernel.as_ref().map(|kernel| kernel.weights.clone()),
+            birth_probability: self.birth_probability,
+            survival_probability: self.survival_probability,
+            update_mode: self.update_mode,
+            edge_mode: self.edge_mode,
+            edit_policy: self.edit_policy,
+            out_of_bounds: self.out_of_bounds,
+            bit_order: self.bit_order,
+            fit_policy: self.fit_policy,
+            target_fps: self.governor.target_interval.map_or(0.0, |interval| 1000.0 / interval),
+            background_interval_ms: self.background_interval_ms,
+            generation_duration_ms: self.timestep.duration_ms,
+            warmup_generations: self.warmup_generations,
+            garden: (period, depth, density),
+            population_target: (target, gain),
+            max_period: self.period_detector.max_period(),
+            neighbour_cache: self.cache_neighbours,
+            spaceship_tracking: self.spaceships.enabled(),
+            centroid_tracking: self.centroid.enabled,
+            console_logging: self.console_logging,
+            hue_cycle: self.hue_speed,
+            color_components: self.color_components,
+            show_ghost: self.show_ghost,
+            birth_flash: self.flash_color.clone(),
+            transparent_background: self.transparent_background,
+            background_color: self.background_color.clone(),
+            motion_blur: self.motion_blur,
+            show_border: self.show_border,
+            border_color: self.border_color.clone(),
+            wall_color: self.wall_color.clone(),
+            cell_size: self.grid.cell,
+            density_block: self.density_block,
+            seam_margin: self.seam_margin,
+            pixel_ratio: self.pixel_ratio,
+            fit_canvas: self.fit_canvas,
+        }
+    }
+    /// Applies every setting of `config`, leaving the cells alone. Settings
+    /// whose setters reset state (period detection, spaceship and centroid
+    /// tracking, the fixed timestep clock) are only applied when they
+    /// change, and a generation in progress is restarted.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), JsValue> {
+        let current = self.config();
+        self.use_rule(config.rule.clone());
+        self.rule_regions = config.rule_regions.clone();
+        self.active_region = config.active_region;
+        self.kernel = None;
+        if let Some(weights) = &config.kernel {
+            self.set_kernel(weights)?;
+        }
+        self.set_birth_probability(config.birth_probability);
+        self.set_survival_probability(config.survival_probability);
+        self.update_mode = config.update_mode;
+        self.edge_mode = config.edge_mode;
+        self.edit_policy = config.edit_policy;
+        self.out_of_bounds = config.out_of_bounds;
+        self.bit_order = config.bit_order;
+        self.fit_policy = config.fit_policy;
+        if config.target_fps != current.target_fps {
+            self.set_target_fps(config.target_fps);
+        }
+        self.set_background_interval(config.background_interval_ms);
+        if config.generation_duration_ms != current.generation_duration_ms {
+            self.set_generation_duration(config.generation_duration_ms);
+        }
+        self.warmup_generations = config.warmup_generations;
+        let (period, depth, density) = config.garden;
+        self.set_garden(period, depth, density);
+        let (target, gain) = config.population_target;
+        self.set_population_target(target, gain);
+        if config.max_period != current.max_period {
+            self.set_max_period(config.max_period);
+        }
+        if config.neighbour_cache != current.neighbour_cache {
+            self.set_neighbour_cache(config.neighbour_cache);
+        }
+        if config.spaceship_tracking != current.spaceship_tracking {
+            self.set_spaceship_tracking(config.spaceship_tracking);
+        }
+        if config.centroid_tracking != current.centroid_tracking {
+            self.track_centroid(config.centroid_tracking);
+        }
+        self.console_logging = config.console_logging;
+
+        self.color_components = config.color_components;
+        self.show_ghost = config.show_ghost;
+        self.flash_color = config.birth_flash.clone();
+        self.transparent_background = config.transparent_background;
+        self.background_color = config.background_color.clone();
+        self.motion_blur = config.motion_blur.clamp(0.0, 0.99);
+        self.show_border = config.show_border;
+        self.border_color = config.border_color.clone();
+        self.wall_color = config.wall_color.clone();
+        self.density_block = config.density_block.max(1);
+        self.seam_margin = config.seam_margin.max(0);
+        self.fit_canvas = config.fit_canvas.filter(|&(width, height)| width > 0.0 && height > 0.0);
+        self.abort_generation();
+        #[cfg(feature = "render")]
+        {
+            if config.cell_size != current.cell_size {
+                self.set_cell_size(config.cell_size)?;
+            }
+            if config.pixel_ratio != current.pixel_ratio {
+                self.set_pixel_ratio(config.pixel_ratio)?;
+            }
+            self.set_hue_cycle(config.hue_cycle);
+        }
+        #[cfg(not(feature = "render"))]
+        {
+            self.grid.cell = config.cell_size.max(1);
+            self.pixel_ratio = if config.pixel_ratio > 0.0 { config.pixel_ratio } else { 1.0 };
+            self.hue_speed = config.hue_cycle;
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Every setting as a JSON object (see `Config`); the board isn't in
+    /// it, so it can be applied to any board with `apply_config`.
+    pub fn export_config(&self) -> Result<String, JsValue> {
+        let json = js_sys::JSON::stringify(&self.config().to_js()?)?;
+        Ok(json.into())
+    }
+    /// Applies settings exported by `export_config`. Keys left out keep
+    /// their current value, so a partial config works too; an unknown
+    /// name or a value of the wrong type fails before anything changes.
+    /// The cells are never touched.
+    pub fn apply_config(&mut self, json: &str) -> Result<(), JsValue> {
+        let mut config = self.config();
+        config.read(&js_sys::JSON::parse(json)?)?;
+        self.set_config(&config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_a_config_leaves_the_cells_alone() {
+        let mut source = Game::headless(8);
+        source.use_rule(Rule::parse("B36/S23").unwrap());
+        let mut game = Game::headless(8);
+        game.set_wall(2, 2, true);
+        game.set_wall_polarity(true);
+        game.state.set(5, 5, true);
+        game.set_config(&source.config()).unwrap();
+        assert_eq!(game.rule.to_string(), "B36/S23");
+        assert!(game.wall_polarity);
+        assert_eq!(game.state.live_cells().collect::<Vec<_>>(), vec![(2, 2), (5, 5)]);
+    }
+}
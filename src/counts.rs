@@ -0,0 +1,86 @@
+use wasm_bindgen::prelude::*;
+
+use crate::edges::EdgeMode;
+use crate::game::{Game, UpdateMode};
+use crate::rule::Rule;
+
+impl Game {
+    // the cache holds plain radius 1 counts of the committed board, so it
+    // has the same limits as the summed-area table; it is only kept while
+    // some cell reads its count from it, which neither the bit-parallel
+    // step nor larger radii do
+    pub(crate) fn uses_neighbour_cache(&self) -> bool {
+        let reads_counts = |rule: &Rule| rule.radius == 1 && rule.isotropic.is_none();
+        self.cache_neighbours
+            && self.update_mode == UpdateMode::Synchronous
+            && self.edge_mode == EdgeMode::Dead
+            && self.kernel.is_none()
+            && self.bit_parallel_rule().is_none()
+            && (reads_counts(&self.rule) || self.rule_regions.iter().any(|(_, rule)| reads_counts(rule)))
+    }
+    // called by every edit: the next generation recounts the whole board
+    pub(crate) fn invalidate_neighbour_cache(&mut self) {
+        self.neighbour_counts = vec![];
+    }
+    // called when a generation opens
+    pub(crate) fn prepare_neighbour_cache(&mut self) {
+        if !self.uses_neighbour_cache() {
+            self.neighbour_counts = vec![];
+        } else if self.neighbour_counts.is_empty() {
+            let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+            self.neighbour_counts = vec![vec![0; height]; width];
+            let live: Vec<(usize, usize)> = self.state.live_cells().collect();
+            for (col, row) in live {
+                self.shift_neighbour_counts(col, row, true);
+            }
+        }
+    }
+    // called on commit, before the open generation replaces the board:
+    // only the neighbourhoods of cells that were born or died change
+    pub(crate) fn update_neighbour_cache(&mut self) {
+        if self.neighbour_counts.is_empty() {
+            return;
+        }
+        let changes: Vec<(usize, usize, bool)> = self.state.changed_cells(&self.interim_state).collect();
+        for (col, row, alive) in changes {
+            self.shift_neighbour_counts(col, row, alive);
+        }
+    }
+    fn shift_neighbour_counts(&mut self, col: usize, row: usize, born: bool) {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        for ncol in col.saturating_sub(1)..(col + 2).min(width) {
+            for nrow in row.saturating_sub(1)..(row + 2).min(height) {
+                if ncol == col && nrow == row {
+                    continue;
+                }
+                let count = &mut self.neighbour_counts[ncol][nrow];
+                if born {
+                    *count += 1;
+                } else {
+                    *count -= 1;
+                }
+            }
+        }
+    }
+    pub(crate) fn get_cached_count(&self, i: i16, j: i16) -> u32 {
+        self.neighbour_counts[j as usize][i as usize] as u32
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Keeps a count of live neighbours per cell between generations,
+    /// updated only around the cells that were born or died, so radius 1
+    /// rules read a cell's count instead of walking its neighbourhood.
+    /// Pays off on sparse boards; on busy ones the upkeep costs about what
+    /// it saves. Only used by synchronous updates with dead edges and
+    /// without a kernel, and off by default. A plain B/S rule over the
+    /// whole board already steps a word of cells at a time and has no use
+    /// for the cache, which is then only kept when walls, rule regions, an
+    /// active region, a row-major layout or a Generations rule send the
+    /// board down the cell by cell path.
+    pub fn set_neighbour_cache(&mut self, enabled: bool) {
+        self.cache_neighbours = enabled;
+        self.invalidate_neighbour_cache();
+    }
+}
@@ -0,0 +1,58 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::{Game, UpdateMode};
+use crate::rule::Rule;
+
+impl Game {
+    // dying cells of a Generations rule are neither alive nor able to be born
+    pub(crate) fn is_dying(&self, col: usize, row: usize) -> bool {
+        self.decay
+            .get(col)
+            .and_then(|decay| decay.get(row))
+            .is_some_and(|&steps| steps > 0)
+    }
+    // one more than the dying states, as `advance_decay` takes one off
+    pub(crate) fn mark_dying(&mut self, col: usize, row: usize) {
+        if self.decay.is_empty() {
+            self.decay = vec![vec![0; self.grid.height as usize]; self.grid.width as usize];
+        }
+        self.decay[col][row] = self.rule.states - 1;
+    }
+    // called on commit, before the open generation replaces the board:
+    // cells that just died start dying and the others move one state on
+    pub(crate) fn advance_decay(&mut self) {
+        if self.rule.states <= 2 {
+            self.decay = vec![];
+            return;
+        }
+        // a sequential update already marked them while overwriting the board
+        if self.update_mode == UpdateMode::Synchronous {
+            let changes: Vec<(usize, usize, bool)> = self.state.changed_cells(&self.interim_state).collect();
+            for (col, row, alive) in changes {
+                if !alive && !self.is_wall(col, row) {
+                    self.mark_dying(col, row);
+                }
+            }
+        }
+        for steps in self.decay.iter_mut().flatten() {
+            *steps = steps.saturating_sub(1);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Applies a Generations rule written `survival/birth/states`, e.g.
+    /// `345/2/4` for Star Wars or `/2/3` for Brian's Brain: a live cell
+    /// that fails to survive goes through `states - 2` dying states, one per
+    /// generation, before it is dead again. Dying cells do not count as
+    /// neighbours and cannot be born; they are drawn fading out. A
+    /// generation in progress is restarted.
+    pub fn set_generations_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        self.rule = Rule::parse_generations(rule)?;
+        self.decay = vec![];
+        self.abort_generation();
+        self.redraw();
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+impl Game {
+    // internal warnings go through here rather than straight to the console
+    pub(crate) fn diagnostic(&self, message: &str) {
+        if let Some(sink) = &self.log_sink {
+            // a throwing sink has nowhere left to report to
+            let _ = sink.call1(&JsValue::NULL, &JsValue::from_str(message));
+        } else if self.console_logging {
+            web_sys::console::log_1(&JsValue::from_str(message));
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Sends this game's diagnostics, such as an event callback throwing,
+    /// to `sink(message)` instead of the console; `None` goes back to the
+    /// console.
+    pub fn set_log_sink(&mut self, sink: Option<js_sys::Function>) {
+        self.log_sink = sink;
+    }
+    /// Whether diagnostics go to the console when no sink is set (the
+    /// default); off silences them.
+    pub fn set_console_logging(&mut self, enabled: bool) {
+        self.console_logging = enabled;
+    }
+}
@@ -0,0 +1,61 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// What lies past the edges of the board when counting neighbours.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EdgeMode {
+    /// Dead cells: patterns break up against the edge.
+    #[default]
+    Dead,
+    /// The opposite edge, as on a torus, so gliders leaving on one side come
+    /// back on the other.
+    Wrap,
+    /// The board reflected in the edge: the cell just past it mirrors the
+    /// edge cell.
+    Mirror,
+}
+
+// index along an axis of `len` cells that `position` reads under `mode`
+pub(crate) fn edge_index(position: i16, len: i16, mode: EdgeMode) -> Option<usize> {
+    if position >= 0 && position < len {
+        return Some(position as usize);
+    }
+    match mode {
+        EdgeMode::Dead => None,
+        EdgeMode::Wrap => Some(position.rem_euclid(len) as usize),
+        EdgeMode::Mirror => {
+            let folded = position.rem_euclid(2 * len);
+            Some(if folded < len { folded } else { 2 * len - 1 - folded } as usize)
+        }
+    }
+}
+
+impl Game {
+    // whether the board is a torus, for analysis that follows clusters
+    // across the seam
+    pub(crate) fn wraps_edges(&self) -> bool {
+        self.edge_mode == EdgeMode::Wrap
+    }
+    // whether the neighbour at column `x`, row `y` is alive, which may be
+    // past the edge
+    pub(crate) fn neighbour_alive(&self, x: i16, y: i16) -> bool {
+        match (edge_index(x, self.grid.width, self.edge_mode), edge_index(y, self.grid.height, self.edge_mode)) {
+            (Some(col), Some(row)) => self.state.get(col, row),
+            _ => false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// What neighbour counts see past the edges; `Dead` by default. The
+    /// summed-area table and the neighbour cache only work with dead edges,
+    /// so the other modes count every neighbourhood cell by cell.
+    pub fn set_edge_mode(&mut self, mode: EdgeMode) {
+        self.edge_mode = mode;
+        self.invalidate_neighbour_cache();
+        self.abort_generation();
+    }
+}
@@ -0,0 +1,327 @@
+use rand::Rng;
+use std::cmp::{max, min};
+use wasm_bindgen::prelude::*;
+
+use crate::bits::BitGrid;
+use crate::edges::edge_index;
+use crate::game::Game;
+
+/// What cell accessors do with coordinates outside the grid.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutOfBounds {
+    /// Reads return dead and writes do nothing.
+    #[default]
+    Ignore,
+    /// Coordinates past an edge land on the cell neighbour counts read
+    /// there under the `EdgeMode`: around on the opposite edge under
+    /// `Wrap`, reflected back under `Mirror`. Past a `Dead` edge there is
+    /// no such cell, so they are ignored. The edge mode covers both axes;
+    /// there is no setting for wrapping only one of them.
+    Wrap,
+    /// The call fails with an error.
+    Error,
+}
+
+/// What a cell edit does while a generation is being computed across
+/// frames.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EditPolicy {
+    /// The edit waits for the generation in progress to be committed (or
+    /// dropped) and then applies to the new board, so the next generation
+    /// is the first to see it. Reads return the old value until then.
+    Queue,
+    /// The edit applies right away and the generation in progress is
+    /// dropped, to be computed again from the edited board.
+    #[default]
+    Restart,
+}
+
+// an edit held back until the open generation is committed or dropped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Edit {
+    Cell(bool),
+    Wall(bool),
+}
+
+impl Game {
+    // board position of (x, y) under the out-of-bounds policy
+    fn resolve_cell(&self, x: i16, y: i16) -> Result<Option<(usize, usize)>, JsValue> {
+        let (width, height) = (self.grid.width, self.grid.height);
+        if self.grid.contains(x, y) {
+            return Ok(Some((x as usize, y as usize)));
+        }
+        match self.out_of_bounds {
+            OutOfBounds::Ignore => Ok(None),
+            OutOfBounds::Wrap => Ok(edge_index(x, width, self.edge_mode).zip(edge_index(y, height, self.edge_mode))),
+            OutOfBounds::Error => Err(format!("cell ({}, {}) is outside the {}x{} grid", x, y, width, height).into()),
+        }
+    }
+    fn check_row(&self, y: i16) -> Result<(), JsValue> {
+        if y < 0 || y >= self.grid.height {
+            return Err(format!("row {} is outside the {}x{} grid", y, self.grid.width, self.grid.height).into());
+        }
+        Ok(())
+    }
+    // whether `edit` has to wait for the open generation, in which case it
+    // is queued; under `Restart` the generation is dropped instead
+    pub(crate) fn hold_edit(&mut self, col: usize, row: usize, edit: Edit) -> bool {
+        if self.generation_open {
+            match self.edit_policy {
+                EditPolicy::Queue => {
+                    self.queued_edits.push((col, row, edit));
+                    return true;
+                }
+                EditPolicy::Restart => self.abort_generation(),
+            }
+        }
+        false
+    }
+    pub(crate) fn write_cell(&mut self, col: usize, row: usize, alive: bool) {
+        if self.is_wall(col, row) || self.hold_edit(col, row, Edit::Cell(alive)) {
+            return;
+        }
+        self.state.set(col, row, alive);
+        // a cell brought back to life is no longer dying
+        if alive && self.is_dying(col, row) {
+            self.decay[col][row] = 0;
+        }
+        self.invalidate_neighbour_cache();
+    }
+    // called once the open generation is committed or dropped
+    pub(crate) fn apply_queued_edits(&mut self) {
+        for (col, row, edit) in std::mem::take(&mut self.queued_edits) {
+            if col < self.state.width() && row < self.state.height() {
+                match edit {
+                    Edit::Cell(alive) => self.write_cell(col, row, alive),
+                    Edit::Wall(on) => self.write_wall(col, row, on),
+                }
+            }
+        }
+    }
+    // common tail of every edit that rewrites the board wholesale
+    pub(crate) fn board_replaced(&mut self) {
+        self.queued_edits = vec![];
+        self.abort_generation();
+        self.decay = vec![];
+        self.invalidate_neighbour_cache();
+        self.last_activity = 0;
+        self.last_changes = vec![];
+        self.last_births = vec![];
+        self.apply_walls();
+        self.period_detector.reset();
+        self.centroid.reset();
+        self.run_hash = self.state_hash();
+        self.redraw();
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Changes the grid to `width` x `height` cells, keeping the cells (and
+    /// walls) that still fit where they are and filling new space with dead
+    /// cells. The canvas is resized to match and a generation in progress
+    /// is dropped.
+    pub fn resize(&mut self, width: i16, height: i16) {
+        let (width, height) = (max(width, 1), max(height, 1));
+        let (cols, rows) = (width as usize, height as usize);
+        self.state = self.state.resized(cols, rows);
+        for col in self.walls.iter_mut() {
+            col.resize(rows, false);
+        }
+        if !self.walls.is_empty() {
+            self.walls.resize(cols, vec![false; rows]);
+        }
+        self.grid.width = width;
+        self.grid.height = height;
+        self.summed_area = vec![];
+        // the canvas element exists, so sizing it again can't fail
+        let _ = self.size_canvas();
+        self.board_replaced();
+    }
+    /// Moves every live cell by `(dx, dy)`. Cells pushed past an edge come
+    /// back on the opposite side when `wrap` is set and are dropped
+    /// otherwise. Walls stay where they are.
+    pub fn translate_cells(&mut self, dx: i16, dy: i16, wrap: bool) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let mut moved = BitGrid::with_layout(width as usize, height as usize, self.state.layout());
+        for (col_num, row_num) in self.state.live_cells() {
+            let mut x = col_num as i16 + dx;
+            let mut y = row_num as i16 + dy;
+            if wrap {
+                x = x.rem_euclid(width);
+                y = y.rem_euclid(height);
+            } else if !self.grid.contains(x, y) {
+                continue;
+            }
+            moved.set(x as usize, y as usize, true);
+        }
+        self.state = moved;
+        self.board_replaced();
+    }
+    /// How `get_cell`, `set_cell` and `paint` treat coordinates outside
+    /// the grid; `Ignore` by default. `Wrap` follows the edge mode, so it
+    /// only wraps on a board whose edges wrap.
+    pub fn set_out_of_bounds(&mut self, policy: OutOfBounds) {
+        self.out_of_bounds = policy;
+    }
+    /// How `set_cell`, `toggle_cell`, `paint`, `set_row` and
+    /// `randomize_region` behave while a generation is in progress;
+    /// `Restart` by default. Edits queued under `Queue` still apply when
+    /// switching policies.
+    pub fn set_edit_policy(&mut self, policy: EditPolicy) {
+        self.edit_policy = policy;
+    }
+    pub fn get_cell(&self, x: i16, y: i16) -> Result<bool, JsValue> {
+        Ok(self.resolve_cell(x, y)?.is_some_and(|(col, row)| self.state.get(col, row)))
+    }
+    /// Sets one cell. Walls keep their state.
+    pub fn set_cell(&mut self, x: i16, y: i16, alive: bool) -> Result<(), JsValue> {
+        if let Some((col, row)) = self.resolve_cell(x, y)? {
+            self.write_cell(col, row, alive);
+            self.redraw();
+        }
+        Ok(())
+    }
+    /// Board as one byte per cell, 1 alive and 0 dead, row by row from the
+    /// top left corner: cell `(x, y)` is byte `y * width + x`. Eight times
+    /// the size of `cells`, but it can be indexed directly.
+    pub fn get_state(&self) -> Vec<u8> {
+        let width = self.grid.width as usize;
+        let mut state = vec![0u8; width * self.grid.height as usize];
+        for (x, y) in self.iter_live() {
+            state[y as usize * width + x as usize] = 1;
+        }
+        state
+    }
+    /// Row `y` packed one bit per cell from the left, in the `set_bit_order`
+    /// order, in `(width + 7) / 8` bytes: the bits `cells` holds for that
+    /// row, but starting on a byte boundary.
+    pub fn get_row(&self, y: i16) -> Result<Vec<u8>, JsValue> {
+        self.check_row(y)?;
+        let mut row = vec![0u8; (self.grid.width as usize).div_ceil(8)];
+        for col_num in 0..self.state.width() {
+            if self.state.get(col_num, y as usize) {
+                row[col_num / 8] |= self.bit_order.mask(col_num);
+            }
+        }
+        Ok(row)
+    }
+    /// Replaces row `y` with one packed as `get_row` produces it. Walls
+    /// keep their state.
+    pub fn set_row(&mut self, y: i16, row: &[u8]) -> Result<(), JsValue> {
+        self.check_row(y)?;
+        let len = (self.grid.width as usize).div_ceil(8);
+        if row.len() != len {
+            return Err(format!("expected {} bytes for a row of {} cells, got {}", len, self.grid.width, row.len()).into());
+        }
+        for col in 0..self.grid.width as usize {
+            self.write_cell(col, y as usize, row[col / 8] & self.bit_order.mask(col) != 0);
+        }
+        self.redraw();
+        Ok(())
+    }
+    /// Re-randomizes the `width` x `height` rectangle at `(x, y)`, clipped
+    /// to the grid, so each cell is alive with probability `density`,
+    /// drawing from the seeded generator. The rest of the board and the
+    /// walls are left alone.
+    pub fn randomize_region(&mut self, x: i16, y: i16, width: i16, height: i16, density: f64) {
+        let density = density.clamp(0.0, 1.0);
+        let (left, top) = (max(x, 0), max(y, 0));
+        let right = min(x.saturating_add(width), self.grid.width);
+        let bottom = min(y.saturating_add(height), self.grid.height);
+        for col in left..right {
+            for row in top..bottom {
+                let alive = self.rng.gen_bool(density);
+                self.write_cell(col as usize, row as usize, alive);
+            }
+        }
+        self.period_detector.reset();
+        self.redraw();
+    }
+    /// Flips one cell and returns the population afterwards, saving an
+    /// editor a second call. Walls keep their state.
+    pub fn toggle_cell(&mut self, x: i16, y: i16) -> Result<u32, JsValue> {
+        if let Some((col, row)) = self.resolve_cell(x, y)? {
+            self.write_cell(col, row, !self.state.get(col, row));
+            self.redraw();
+        }
+        Ok(self.count_population())
+    }
+    /// Sets the square of cells within `radius` of `(x, y)`. With the
+    /// `Error` policy only the centre has to be on the grid; the rest of
+    /// the brush is clipped at the edges.
+    pub fn paint(&mut self, x: i16, y: i16, radius: i16, alive: bool) -> Result<(), JsValue> {
+        self.resolve_cell(x, y)?;
+        for brush_x in x - radius..=x + radius {
+            for brush_y in y - radius..=y + radius {
+                let cell = match self.out_of_bounds {
+                    OutOfBounds::Error => self.resolve_cell(brush_x, brush_y).unwrap_or(None),
+                    _ => self.resolve_cell(brush_x, brush_y)?,
+                };
+                if let Some((col, row)) = cell {
+                    self.write_cell(col, row, alive);
+                }
+            }
+        }
+        self.redraw();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blinker() -> Game {
+        let mut game = Game::headless(6);
+        for x in 1..4 {
+            game.state.set(x, 2, true);
+        }
+        game
+    }
+
+    #[test]
+    fn queued_edits_apply_after_the_commit() {
+        let mut game = blinker();
+        game.set_edit_policy(EditPolicy::Queue);
+        game.begin_generation();
+        game.set_cell(5, 5, true).unwrap();
+        game.set_cell(2, 2, false).unwrap();
+        assert!(game.generation_open);
+        assert!(!game.state.get(5, 5) && game.state.get(2, 2));
+        game.commit_generation();
+        assert_eq!(game.state.live_cells().collect::<Vec<_>>(), vec![(2, 1), (2, 3), (5, 5)]);
+    }
+
+    #[test]
+    fn edits_restart_an_open_generation() {
+        let mut game = blinker();
+        game.set_edit_policy(EditPolicy::Restart);
+        game.begin_generation();
+        game.set_cell(5, 5, true).unwrap();
+        assert!(!game.generation_open);
+        assert!(game.state.get(5, 5) && game.state.get(1, 2));
+        game.step();
+        assert_eq!(game.state.live_cells().collect::<Vec<_>>(), vec![(2, 1), (2, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn wall_edits_follow_the_edit_policy() {
+        let mut game = blinker();
+        game.set_wall_polarity(true);
+        game.set_edit_policy(EditPolicy::Queue);
+        game.begin_generation();
+        game.set_wall(5, 5, true);
+        assert!(game.generation_open && !game.is_wall(5, 5));
+        game.commit_generation();
+        assert!(game.is_wall(5, 5) && game.state.get(5, 5));
+
+        game.set_edit_policy(EditPolicy::Restart);
+        game.begin_generation();
+        game.stamp_frozen_block(0, 0, 2, 2);
+        assert!(!game.generation_open);
+        assert!(game.is_wall(1, 1) && game.state.get(1, 1));
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Widest peak window accepted, keeping the population history bounded.
+const PEAK_WINDOW_LIMIT: u32 = 1024;
+
+/// Population crossings detected at generation boundaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PopulationEvent {
+    /// Population reached `factor` times the population of the last
+    /// growth or decline event (or of the first observed generation).
+    Growth,
+    /// Population fell to `1 / factor` of that same reference.
+    Decline,
+    /// The population `window` generations ago was strictly greater than
+    /// every other population within `window` generations of it.
+    Peak,
+}
+
+impl PopulationEvent {
+    fn name(self) -> &'static str {
+        match self {
+            PopulationEvent::Growth => "growth",
+            PopulationEvent::Decline => "decline",
+            PopulationEvent::Peak => "peak",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PopulationEvents {
+    pub(crate) callback: Option<js_sys::Function>,
+    factor: f64,
+    window: usize,
+    reference: Option<u32>,
+    history: VecDeque<u32>,
+}
+
+impl Default for PopulationEvents {
+    fn default() -> PopulationEvents {
+        PopulationEvents {
+            callback: None,
+            factor: 2.0,
+            window: 5,
+            reference: None,
+            history: VecDeque::new(),
+        }
+    }
+}
+
+impl PopulationEvents {
+    fn reset(&mut self) {
+        self.reference = None;
+        self.history.clear();
+    }
+    fn observe(&mut self, population: u32) -> Vec<PopulationEvent> {
+        let mut events = vec![];
+        let reference = *self.reference.get_or_insert(population);
+        if population as f64 >= reference as f64 * self.factor && population > reference {
+            events.push(PopulationEvent::Growth);
+            self.reference = Some(population);
+        } else if (population as f64) <= reference as f64 / self.factor && population < reference {
+            events.push(PopulationEvent::Decline);
+            self.reference = Some(population);
+        }
+
+        self.history.push_back(population);
+        if self.history.len() > 2 * self.window + 1 {
+            self.history.pop_front();
+        }
+        if self.history.len() == 2 * self.window + 1 {
+            let candidate = self.history[self.window];
+            let is_peak = self.history
+                .iter()
+                .enumerate()
+                .all(|(index, &other)| index == self.window || other < candidate);
+            if is_peak {
+                events.push(PopulationEvent::Peak);
+            }
+        }
+        events
+    }
+}
+
+impl Game {
+    pub(crate) fn count_population(&self) -> u32 {
+        self.state.count_live()
+    }
+    // called once per committed generation
+    pub(crate) fn fire_population_events(&mut self) {
+        if self.population_events.callback.is_none() {
+            return;
+        }
+        let population = self.count_population();
+        for event in self.population_events.observe(population) {
+            if let Some(callback) = &self.population_events.callback {
+                // a throwing listener must not stop the simulation
+                let result = callback.call2(&JsValue::NULL,
+                                            &JsValue::from_str(event.name()),
+                                            &JsValue::from(population));
+                if let Err(error) = result {
+                    self.diagnostic(&format!("population event callback threw: {:?}", error));
+                }
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Calls `callback(event, population)` after a generation whose
+    /// population triggers an event: `"growth"` when it reaches `factor`
+    /// times the population of the previous growth/decline event,
+    /// `"decline"` when it falls to `1 / factor` of it, and `"peak"` when
+    /// the population `window` generations ago is strictly above every
+    /// other population within `window` generations of it (so peaks are
+    /// reported `window` generations late). Pass `undefined` to stop.
+    pub fn set_population_callback(&mut self, callback: Option<js_sys::Function>) {
+        self.population_events.callback = callback;
+        self.population_events.reset();
+    }
+    /// Growth/decline ratio, 2 by default (doubling and halving).
+    pub fn set_population_factor(&mut self, factor: f64) {
+        self.population_events.factor = factor.max(1.0);
+        self.population_events.reset();
+    }
+    /// Half width, in generations, of the window a peak must dominate,
+    /// from 1 to 1024.
+    pub fn set_peak_window(&mut self, window: u32) {
+        self.population_events.window = window.clamp(1, PEAK_WINDOW_LIMIT) as usize;
+        self.population_events.reset();
+    }
+}
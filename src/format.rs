@@ -0,0 +1,296 @@
+use std::ops::RangeInclusive;
+use wasm_bindgen::prelude::*;
+
+use crate::game::{BitOrder, Game, RNG_STATE_LEN};
+use crate::rule::Rule;
+
+const MAGIC: &[u8; 4] = b"LIFE";
+/// Bumped whenever the layout changes; older versions stay readable.
+const VERSION: u8 = 5;
+
+// Version 1 layout, integers little endian:
+//   magic "LIFE", version u8, width u16, height u16,
+//   rule radius u8, then birth and survival ranges, each as a u8 count
+//   followed by (start u16, end u16) pairs,
+//   then the board packed as `Game::cells` packs it, least significant
+//   bit first.
+// Version 2 appends the generator position as `Game::rng_state` encodes it.
+// Version 3 adds the rule's state count, a u8, after the survival ranges.
+// Version 4 follows it with the notation of an isotropic rule, as a u8
+// length and that many UTF-8 bytes (length 0 for other rules).
+// Version 5 appends the walls: a u8 that is 0 without walls, 1 for dead
+// and 2 for live walls, then in the last two cases the walls packed the
+// way the cells are.
+
+fn write_ranges(bytes: &mut Vec<u8>, ranges: &[RangeInclusive<u32>]) {
+    bytes.push(ranges.len() as u8);
+    for range in ranges {
+        bytes.extend_from_slice(&(*range.start() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(*range.end() as u16).to_le_bytes());
+    }
+}
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// unpadded base64url, safe to drop into a URL fragment as is
+fn encode_base64url(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, &byte)| {
+            bits | (byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..=chunk.len() {
+            encoded.push(BASE64URL[(bits >> (18 - 6 * index) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+fn decode_base64url(encoded: &str) -> Result<Vec<u8>, JsValue> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut len = 0;
+    for symbol in encoded.bytes() {
+        let value = BASE64URL
+            .iter()
+            .position(|&candidate| candidate == symbol)
+            .ok_or_else(|| format!("invalid character {:?} in share string", symbol as char))?;
+        bits = bits << 6 | value as u32;
+        len += 6;
+        if len >= 8 {
+            len -= 8;
+            bytes.push((bits >> len) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], JsValue> {
+        let end = self.offset + len;
+        if end > self.bytes.len() {
+            return Err(format!("board data truncated at byte {}", self.bytes.len()).into());
+        }
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+    fn u8(&mut self) -> Result<u8, JsValue> {
+        Ok(self.take(1)?[0])
+    }
+    fn u16(&mut self) -> Result<u16, JsValue> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+    fn ranges(&mut self) -> Result<Vec<RangeInclusive<u32>>, JsValue> {
+        let len = self.u8()?;
+        let mut ranges = vec![];
+        for _ in 0..len {
+            let start = self.u16()? as u32;
+            let end = self.u16()? as u32;
+            ranges.push(start..=end);
+        }
+        Ok(ranges)
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Compact binary snapshot of the board, its rule, the random generator
+    /// position and the walls: a versioned header, the cells packed one bit
+    /// each, the generator state, then the walls packed the same way.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.grid.width as u16).to_le_bytes());
+        bytes.extend_from_slice(&(self.grid.height as u16).to_le_bytes());
+        bytes.push(self.rule.radius as u8);
+        write_ranges(&mut bytes, &self.rule.birth);
+        write_ranges(&mut bytes, &self.rule.survival);
+        bytes.push(self.rule.states);
+        let notation = self.rule.isotropic.as_ref().map_or("", |table| table.notation.as_str());
+        bytes.push(notation.len() as u8);
+        bytes.extend_from_slice(notation.as_bytes());
+        bytes.extend_from_slice(&self.pack_cells(BitOrder::LsbFirst));
+        bytes.extend_from_slice(&self.rng_state());
+        if self.walls.is_empty() {
+            bytes.push(0);
+        } else {
+            bytes.push(if self.wall_polarity { 2 } else { 1 });
+            let width = self.grid.width as usize;
+            let mut walls = vec![0u8; self.packed_len()];
+            for (col_num, col) in self.walls.iter().enumerate() {
+                for row_num in (0..col.len()).filter(|&row_num| col[row_num]) {
+                    let index = row_num * width + col_num;
+                    walls[index / 8] |= BitOrder::LsbFirst.mask(index);
+                }
+            }
+            bytes.extend_from_slice(&walls);
+        }
+        bytes
+    }
+    /// Restores a snapshot made by `to_bytes`, replacing the board size,
+    /// rule and cells, and the generator position and walls when the
+    /// snapshot has them.
+    pub fn from_bytes(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("not a board snapshot".into());
+        }
+        let version = reader.u8()?;
+        if version == 0 || version > VERSION {
+            return Err(format!("unsupported board snapshot version {}", version).into());
+        }
+        let width = reader.u16()?;
+        let height = reader.u16()?;
+        for &(side, len) in &[("width", width), ("height", height)] {
+            if len == 0 || len > i16::MAX as u16 {
+                return Err(format!("invalid board {} {} in snapshot", side, len).into());
+            }
+        }
+        let radius = reader.u8()? as i16;
+        if radius == 0 {
+            return Err("invalid rule radius 0 in snapshot".into());
+        }
+        let birth = reader.ranges()?;
+        let survival = reader.ranges()?;
+        let states = if version >= 3 { reader.u8()?.max(2) } else { 2 };
+        let isotropic = if version >= 4 {
+            let len = reader.u8()? as usize;
+            let notation = std::str::from_utf8(reader.take(len)?).map_err(|_| "invalid rule notation")?;
+            if len > 0 {
+                Rule::parse_isotropic(notation)?.isotropic
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let cells_len = (width as usize * height as usize).div_ceil(8);
+        let (cells, rng_state) = if version >= 2 {
+            (reader.take(cells_len)?, Some(reader.take(RNG_STATE_LEN)?))
+        } else {
+            (&bytes[reader.offset..], None)
+        };
+        // (polarity, walls), the polarity None when the snapshot has no
+        // walls; None altogether before walls were saved
+        let walls = if version >= 5 {
+            match reader.u8()? {
+                0 => Some((None, vec![])),
+                marker @ 1..=2 => {
+                    let packed = reader.take(cells_len)?;
+                    let walls = (0..width as usize)
+                        .map(|col_num| {
+                            (0..height as usize)
+                                .map(|row_num| {
+                                    let index = row_num * width as usize + col_num;
+                                    packed[index / 8] & BitOrder::LsbFirst.mask(index) != 0
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    Some((Some(marker == 2), walls))
+                }
+                marker => return Err(format!("invalid walls marker {} in snapshot", marker).into()),
+            }
+        } else {
+            None
+        };
+        let size = (self.grid.width, self.grid.height);
+        self.grid.width = width as i16;
+        self.grid.height = height as i16;
+        if let Err(error) = self.unpack_cells(cells, BitOrder::LsbFirst) {
+            self.grid.width = size.0;
+            self.grid.height = size.1;
+            return Err(error);
+        }
+        if let Some((polarity, walls)) = walls {
+            self.walls = walls;
+            if let Some(polarity) = polarity {
+                self.wall_polarity = polarity;
+            }
+            self.apply_walls();
+            self.run_hash = self.state_hash();
+            self.redraw();
+        }
+        self.rule = Rule {
+            radius,
+            survival,
+            birth,
+            states,
+            isotropic,
+        };
+        if let Some(rng_state) = rng_state {
+            self.set_rng_state(rng_state)?;
+        }
+        if (width as i16, height as i16) != size {
+            self.size_canvas()?;
+            self.redraw();
+        }
+        Ok(())
+    }
+    /// `to_bytes` as unpadded base64url, suitable for `location.hash`.
+    pub fn to_share_string(&self) -> String {
+        encode_base64url(&self.to_bytes())
+    }
+    /// Restores a board from a `to_share_string` string; a leading `#`, as
+    /// in `location.hash`, is ignored.
+    pub fn from_share_string(&mut self, share: &str) -> Result<(), JsValue> {
+        let bytes = decode_base64url(share.trim_start_matches('#'))?;
+        self.from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_round_trip() {
+        let mut game = Game::headless_rect(37, 21);
+        game.set_seed(110);
+        game.randomize_region(0, 0, 37, 21, 0.4);
+        game.rule = Rule::parse("B2-a/S12").unwrap();
+        game.set_wall_polarity(true);
+        game.set_wall(3, 4, true);
+        game.set_wall(36, 20, true);
+        let bytes = game.to_bytes();
+
+        let mut restored = Game::headless(5);
+        restored.from_bytes(&bytes).unwrap();
+        assert_eq!(restored.to_bytes(), bytes);
+        assert_eq!(restored.state, game.state);
+        assert_eq!(restored.rule.to_string(), game.rule.to_string());
+        assert_eq!(restored.rng_state(), game.rng_state());
+        assert_eq!(restored.walls, game.walls);
+        assert!(restored.wall_polarity);
+        assert!(restored.state.get(3, 4));
+    }
+
+    #[test]
+    fn snapshots_without_walls_clear_them() {
+        let mut game = Game::headless(8);
+        game.set_wall(1, 1, true);
+        game.from_bytes(&Game::headless(8).to_bytes()).unwrap();
+        assert!(game.walls.is_empty());
+    }
+
+    #[test]
+    fn base64url_round_trips() {
+        for len in 0..=20usize {
+            let bytes: Vec<u8> = (0..len).map(|index| (index * 97 + 251) as u8).collect();
+            let encoded = encode_base64url(&bytes);
+            assert_eq!(encoded.len(), (len * 4).div_ceil(3));
+            assert!(encoded.bytes().all(|symbol| BASE64URL.contains(&symbol)));
+            assert_eq!(decode_base64url(&encoded).unwrap(), bytes);
+        }
+        assert_eq!(encode_base64url(b"Man"), "TWFu");
+        assert_eq!(encode_base64url(&[0xfb, 0xff]), "-_8");
+    }
+}
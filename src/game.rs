@@ -0,0 +1,846 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "render")]
+use std::cell::RefCell;
+use std::cmp::{max, min};
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+use crate::bits::{BitGrid, Layout};
+use crate::breakpoint::Breakpoint;
+use crate::centroid::CentroidTracker;
+use crate::edges::EdgeMode;
+use crate::edit::{Edit, EditPolicy, OutOfBounds};
+use crate::events::PopulationEvents;
+use crate::garden::Garden;
+use crate::governor::{Governor, DEFAULT_FRAME_BUDGET_MS};
+use crate::homeostasis::Homeostasis;
+use crate::kernel::Kernel;
+use crate::pattern::{Align, FitPolicy, Pattern};
+use crate::period::PeriodDetector;
+use crate::region::Rect;
+use crate::spaceships::SpaceshipTracker;
+use crate::timestep::FixedTimestep;
+#[cfg(feature = "render")]
+use crate::render::Listener;
+#[cfg(feature = "render")]
+use crate::webgl::WebGlRenderer;
+use crate::rule::{array_to_ranges, ranges_to_array, rule_name, Rule};
+
+/// Radius above which neighbour counts are read from the summed-area table
+/// instead of walking the whole neighbourhood.
+const SUMMED_AREA_RADIUS: i16 = 2;
+
+/// Bytes in a `Game::rng_state` snapshot.
+pub(crate) const RNG_STATE_LEN: usize = 24;
+
+// display settings are only read by the `render` feature
+#[cfg_attr(not(feature = "render"), allow(dead_code))]
+#[derive(Debug)]
+pub struct Grid {
+    pub(crate) cell: i16,
+    pub(crate) width: i16,
+    pub(crate) height: i16,
+}
+
+impl Grid {
+    pub(crate) fn contains(&self, x: i16, y: i16) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+}
+
+// Seeking once fills the output buffer, so `get_word_pos` doesn't
+// underflow (a debug build panic) on a generator that produced nothing yet.
+fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_word_pos(0);
+    rng
+}
+
+/// Which board a generation reads neighbours from.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UpdateMode {
+    /// Every cell is computed from the previous, committed generation.
+    #[default]
+    Synchronous,
+    /// Cells are overwritten in scan order (column by column, top to bottom),
+    /// so a cell already sees the new value of the neighbours scanned before
+    /// it. The result is order dependent: mirroring the board or changing
+    /// the scan order changes how it evolves.
+    Sequential,
+}
+
+/// Order of the cells within each byte of a packed board.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BitOrder {
+    /// The first cell of a byte is its least significant bit (`0x01`).
+    #[default]
+    LsbFirst,
+    /// The first cell of a byte is its most significant bit (`0x80`), as
+    /// in most bitmap formats.
+    MsbFirst,
+}
+
+impl BitOrder {
+    // mask of the `index`th packed cell within its byte
+    pub(crate) fn mask(self, index: usize) -> u8 {
+        match self {
+            BitOrder::LsbFirst => 1 << (index % 8),
+            BitOrder::MsbFirst => 0x80 >> (index % 8),
+        }
+    }
+}
+
+/// What `start` fills the board with.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum InitMode {
+    /// Every cell alive or dead with equal odds, from the seeded generator.
+    #[default]
+    Random,
+    /// All cells dead, for drawing a pattern from scratch.
+    Empty,
+    /// One of the `Pattern::named` patterns, centred on an empty board.
+    Pattern(String),
+}
+
+/// The simulation, optionally attached to a canvas. A game built with
+/// `Game::headless` never touches the DOM, so it can run inside a worker
+/// and hand its board to the rendering instance through `cells`. Building
+/// without the default `render` feature leaves only headless games, with
+/// no canvas code at all.
+#[wasm_bindgen]
+#[cfg_attr(not(feature = "render"), allow(dead_code))]
+#[derive(Debug)]
+pub struct Game {
+    pub(crate) grid: Grid,
+    pub(crate) rule: Rule,
+    pub(crate) rule_regions: Vec<(Rect, Rule)>,
+    pub(crate) active_region: Option<Rect>,
+    pub(crate) kernel: Option<Kernel>,
+    pub(crate) birth_probability: f64,
+    pub(crate) survival_probability: f64,
+    pub(crate) update_mode: UpdateMode,
+    pub(crate) edge_mode: EdgeMode,
+    pub(crate) bit_order: BitOrder,
+    pub(crate) init_mode: InitMode,
+    pub(crate) warmup_generations: u32,
+    // packed column by column unless `set_layout` says otherwise:
+    // generations are computed a column at a time, and radius 1 rules a
+    // word of each column at a time
+    pub(crate) state: BitGrid,
+    // the open generation, of which the first `interim_columns` columns
+    // are computed; kept between generations and swapped with `state` on
+    // commit, so the rest holds a stale board
+    pub(crate) interim_state: BitGrid,
+    pub(crate) interim_columns: usize,
+    // copy of the board a sequential generation started from, empty
+    // otherwise
+    pub(crate) generation_base: BitGrid,
+    pub(crate) last_activity: u32,
+    // cells born or dead in the last committed generation
+    pub(crate) last_changes: Vec<(usize, usize)>,
+    pub(crate) last_births: Vec<(usize, usize)>,
+    pub(crate) generation_open: bool,
+    pub(crate) generation: u64,
+    pub(crate) running: bool,
+    pub(crate) breakpoint: Breakpoint,
+    pub(crate) run_hash: u64,
+    pub(crate) seed: u64,
+    pub(crate) rng: ChaCha8Rng,
+    pub(crate) out_of_bounds: OutOfBounds,
+    pub(crate) edit_policy: EditPolicy,
+    // cell and wall edits waiting for the open generation, in order
+    pub(crate) queued_edits: Vec<(usize, usize, Edit)>,
+    pub(crate) fit_policy: FitPolicy,
+    pub(crate) summed_area: Vec<Vec<u32>>,
+    // off only to measure the plain count against the table
+    pub(crate) summed_area_enabled: bool,
+    pub(crate) cache_neighbours: bool,
+    // live neighbours per cell, `neighbour_counts[x][y]`, empty when stale
+    pub(crate) neighbour_counts: Vec<Vec<u8>>,
+    pub(crate) walls: Vec<Vec<bool>>,
+    // remaining dying states per cell under a Generations rule, empty otherwise
+    pub(crate) decay: Vec<Vec<u8>>,
+    pub(crate) wall_polarity: bool,
+    pub(crate) wall_color: String,
+    pub(crate) density_block: i16,
+    pub(crate) fit_canvas: Option<(f64, f64)>,
+    pub(crate) seam_margin: i16,
+    pub(crate) hue_speed: f64,
+    pub(crate) hue_offset: f64,
+    pub(crate) color_components: bool,
+    pub(crate) show_ghost: bool,
+    pub(crate) flash_color: Option<String>,
+    pub(crate) transparent_background: bool,
+    pub(crate) background_color: String,
+    pub(crate) motion_blur: f64,
+    pub(crate) overlay: Vec<(i16, i16, String)>,
+    // frames in, and pixel size of each frame of, the last RGBA capture
+    pub(crate) frame_count: u32,
+    pub(crate) frame_width: u32,
+    pub(crate) frame_height: u32,
+    pub(crate) show_border: bool,
+    pub(crate) border_color: String,
+    pub(crate) population_events: PopulationEvents,
+    pub(crate) garden: Garden,
+    pub(crate) homeostasis: Homeostasis,
+    pub(crate) spaceships: SpaceshipTracker,
+    pub(crate) centroid: CentroidTracker,
+    pub(crate) period_detector: PeriodDetector,
+    pub(crate) frame_budget_ms: f64,
+    pub(crate) governor: Governor,
+    pub(crate) commit_times: VecDeque<f64>,
+    pub(crate) timestep: FixedTimestep,
+    pub(crate) background_interval_ms: f64,
+    pub(crate) pixel_ratio: f64,
+    #[cfg(feature = "render")]
+    pub(crate) canvas: Option<web_sys::HtmlCanvasElement>,
+    #[cfg(feature = "render")]
+    pub(crate) context: Option<web_sys::CanvasRenderingContext2d>,
+    // the board as the canvas shows it, empty when the canvas shows
+    // anything else, for repainting only the cells that changed
+    #[cfg(feature = "render")]
+    pub(crate) drawn_state: RefCell<BitGrid>,
+    // drawing with WebGL instead of `context`
+    #[cfg(feature = "render")]
+    pub(crate) webgl: Option<WebGlRenderer>,
+    // page listeners driving the game, removed when it is destroyed
+    #[cfg(feature = "render")]
+    pub(crate) listeners: Vec<Listener>,
+    // set while generations run in a batch, which draws only the last one
+    pub(crate) rendering_suspended: bool,
+    pub(crate) destroyed: bool,
+    pub(crate) log_sink: Option<js_sys::Function>,
+    pub(crate) console_logging: bool,
+    pub(crate) performance: Option<web_sys::Performance>,
+}
+
+impl Game {
+    /// Headless game over a copy of `state`, indexed `state[x][y]`.
+    ///
+    /// Panics unless every column of `state` has the same length.
+    pub fn from_state(state: Vec<Vec<bool>>) -> Game {
+        let width = state.len();
+        let height = state.first().map_or(0, |col| col.len());
+        assert!(state.iter().all(|col| col.len() == height), "state columns must have the same length");
+        let mut game = Game {
+            state: BitGrid::from_columns(&state, height),
+            ..Game::headless_rect(width as i16, height as i16)
+        };
+        game.run_hash = game.state_hash();
+        game
+    }
+    pub(crate) fn generate_initial_state(&mut self) -> BitGrid {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        let mut initial_store = BitGrid::with_layout(width, height, self.state.layout());
+        for col in 0..width {
+            for row in 0..height {
+                initial_store.set(col, row, self.rng.gen());
+            }
+        }
+        initial_store
+    }
+    /// `set_rule` for a parsed rule.
+    pub fn use_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.decay = vec![];
+        self.abort_generation();
+        self.redraw();
+    }
+    /// Sets what the next `start` fills the board with; an unknown pattern
+    /// name is refused.
+    pub fn set_init_mode(&mut self, mode: InitMode) -> Result<(), String> {
+        if let InitMode::Pattern(name) = &mode {
+            Pattern::named(name).ok_or_else(|| format!("unknown pattern '{}'", name))?;
+        }
+        self.init_mode = mode;
+        Ok(())
+    }
+    pub(crate) fn start(&mut self) -> &mut Game {
+        // so the pattern below isn't queued behind an open generation
+        self.abort_generation();
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        self.state = match self.init_mode {
+            InitMode::Random => self.generate_initial_state(),
+            _ => BitGrid::with_layout(width, height, self.state.layout()),
+        };
+        if let InitMode::Pattern(name) = &self.init_mode {
+            if let Some(pattern) = Pattern::named(name) {
+                self.stamp_pattern(&pattern, Align::Center);
+            }
+        }
+        self.board_replaced();
+        if self.warmup_generations > 0 {
+            self.run_generations(self.warmup_generations);
+            self.redraw();
+        }
+        self
+    }
+    fn next_cell(&self, col_num: usize, row_num: usize, alive: bool) -> bool {
+        if self.is_wall(col_num, row_num) || self.is_frozen(col_num, row_num) {
+            return alive;
+        }
+        if self.is_dying(col_num, row_num) {
+            return false;
+        }
+        let rule = self.rule_at(col_num, row_num);
+        if let Some(table) = &rule.isotropic {
+            let configuration = self.neighbour_configuration(row_num as i16, col_num as i16) as usize;
+            return if alive { table.survival[configuration] } else { table.birth[configuration] };
+        }
+        let nebour_count = self.get_nebour_count(row_num as i16, col_num as i16, rule.radius);
+        if alive {
+            rule.survives(nebour_count)
+        } else {
+            rule.is_born(nebour_count)
+        }
+    }
+    // plain B/S counts for the rules `BitGrid::next_column` can apply: every
+    // cell of a column-major board follows the same radius 1 totalistic
+    // rule and sees dead edges
+    pub(crate) fn bit_parallel_rule(&self) -> Option<([bool; 9], [bool; 9])> {
+        let rule = &self.rule;
+        let plain = rule.radius == 1 && rule.isotropic.is_none() && rule.states == 2;
+        if !plain || self.kernel.is_some() || !self.rule_regions.is_empty() || !self.walls.is_empty()
+            || self.active_region.is_some() || self.edge_mode != EdgeMode::Dead
+            || self.state.layout() != Layout::ColumnMajor
+        {
+            return None;
+        }
+        let (mut birth, mut survival) = ([false; 9], [false; 9]);
+        for count in 0..9 {
+            birth[count] = rule.is_born(count as u32);
+            survival[count] = rule.survives(count as u32);
+        }
+        Some((birth, survival))
+    }
+    // writes column `col_num` of the next generation into `interim_state`
+    pub(crate) fn half_tick(&mut self, col_num: usize) {
+        if let Some((birth, survival)) = self.bit_parallel_rule() {
+            self.state.next_column(col_num, &birth, &survival, &mut self.interim_state);
+            return;
+        }
+        for row_num in 0..self.state.height() {
+            let next = self.next_cell(col_num, row_num, self.state.get(col_num, row_num));
+            self.interim_state.set(col_num, row_num, next);
+        }
+    }
+    // sequential mode: write every cell back into `state` as soon as it is known
+    pub(crate) fn half_tick_in_place(&mut self, col_num: usize) {
+        for row_num in 0..self.state.height() {
+            let alive = self.state.get(col_num, row_num);
+            let next = self.next_cell(col_num, row_num, alive);
+            let next = self.by_chance(col_num, row_num, alive, next);
+            if alive && !next && self.rule.states > 2 && !self.is_wall(col_num, row_num) {
+                self.mark_dying(col_num, row_num);
+            }
+            self.state.set(col_num, row_num, next);
+        }
+        self.interim_state.copy_column(&self.state, col_num);
+    }
+    // without a performance handle (headless) there is no frame budget
+    pub(crate) fn now(&self) -> f64 {
+        self.performance.as_ref().map_or(0.0, |performance| performance.now())
+    }
+    // the table is a snapshot of the committed state, so sequential updates
+    // can't use it, and it only holds plain counts
+    pub(crate) fn uses_summed_area(&self) -> bool {
+        self.summed_area_enabled
+            && self.rule.radius > SUMMED_AREA_RADIUS
+            && self.update_mode == UpdateMode::Synchronous
+            && self.edge_mode == EdgeMode::Dead
+            && self.kernel.is_none()
+    }
+    // summed_area[c][r] holds the live cells in columns 0..c and rows 0..r
+    pub(crate) fn build_summed_area(&mut self) {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        let mut sums = vec![vec![0u32; height + 1]; width + 1];
+        for col in 0..width {
+            for row in 0..height {
+                sums[col + 1][row + 1] = self.state.get(col, row) as u32
+                    + sums[col][row + 1]
+                    + sums[col + 1][row]
+                    - sums[col][row];
+            }
+        }
+        self.summed_area = sums;
+    }
+    fn get_summed_area_count(&self, i: i16, j: i16, radius: i16) -> u32 {
+        let top = max(i - radius, 0) as usize;
+        let bottom = (min(i + radius, self.grid.height - 1) + 1) as usize;
+        let left = max(j - radius, 0) as usize;
+        let right = (min(j + radius, self.grid.width - 1) + 1) as usize;
+        let sums = &self.summed_area;
+        let total = sums[right][bottom] + sums[left][top] - sums[left][bottom] - sums[right][top];
+        total - self.state.get(j as usize, i as usize) as u32
+    }
+    // fraction of live cells in the tile starting at column `col`, row `row`
+    pub(crate) fn tile_density(&self, col: i16, row: i16, width: i16, height: i16) -> f64 {
+        let mut alive = 0;
+        for col_num in col..col + width {
+            for row_num in row..row + height {
+                if self.state.get(col_num as usize, row_num as usize) {
+                    alive += 1;
+                }
+            }
+        }
+        alive as f64 / (width as f64 * height as f64)
+    }
+    fn get_nebour_count(&self, i: i16, j: i16, radius: i16) -> u32 {
+        if let Some(kernel) = &self.kernel {
+            return self.get_weighted_count(kernel, i, j);
+        }
+        if radius == 1 && !self.neighbour_counts.is_empty() {
+            return self.get_cached_count(i, j);
+        }
+        if self.uses_summed_area() {
+            return self.get_summed_area_count(i, j, radius);
+        }
+        let mut count: u32 = 0;
+        for ni in (i - radius)..=(i + radius) {
+            for nj in (j - radius)..=(j + radius) {
+                if ni == i && nj == j { continue; }
+                if self.neighbour_alive(nj, ni) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+    /// Counts large radius neighbourhoods cell by cell instead of from the
+    /// summed-area table, to compare the two.
+    #[cfg(feature = "bench")]
+    pub fn set_summed_area(&mut self, enabled: bool) {
+        self.summed_area_enabled = enabled;
+        self.summed_area = vec![];
+    }
+    /// Runs the neighbour count over every cell, with none of the rest of a
+    /// generation, and returns the sum so the work can't be optimized
+    /// away. Rebuilds the summed-area table first when the rule uses it.
+    #[cfg(feature = "bench")]
+    pub fn count_all_neighbors(&mut self) -> u64 {
+        if self.uses_summed_area() {
+            self.build_summed_area();
+        }
+        let mut total = 0;
+        for col_num in 0..self.state.width() {
+            for row_num in 0..self.state.height() {
+                let radius = self.rule_at(col_num, row_num).radius;
+                total += self.get_nebour_count(row_num as i16, col_num as i16, radius) as u64;
+            }
+        }
+        total
+    }
+    // without the `render` feature there is never a canvas to draw on
+    #[cfg(not(feature = "render"))]
+    pub(crate) fn redraw(&self) {}
+    #[cfg(not(feature = "render"))]
+    pub(crate) fn redraw_changes(&self) {}
+    #[cfg(not(feature = "render"))]
+    pub(crate) fn size_canvas(&self) -> Result<(), JsValue> {
+        Ok(())
+    }
+    // bytes needed to pack the board one bit per cell
+    pub(crate) fn packed_len(&self) -> usize {
+        (self.grid.width as usize * self.grid.height as usize).div_ceil(8)
+    }
+    // snapshots and hashes always pack least significant bit first, so they
+    // don't depend on the order set for `cells`
+    pub(crate) fn pack_cells(&self, order: BitOrder) -> Vec<u8> {
+        let width = self.grid.width as usize;
+        let mut cells = vec![0u8; self.packed_len()];
+        for (col_num, row_num) in self.state.live_cells() {
+            let index = row_num * width + col_num;
+            cells[index / 8] |= order.mask(index);
+        }
+        cells
+    }
+    pub(crate) fn unpack_cells(&mut self, cells: &[u8], order: BitOrder) -> Result<(), JsValue> {
+        let (width, height) = (self.grid.width as usize, self.grid.height as usize);
+        if cells.len() != self.packed_len() {
+            return Err(format!("expected {} bytes for a {}x{} board, got {}",
+                               self.packed_len(), width, height, cells.len()).into());
+        }
+        self.state = BitGrid::with_layout(width, height, self.state.layout());
+        for col_num in 0..width {
+            for row_num in 0..height {
+                let index = row_num * width + col_num;
+                self.state.set(col_num, row_num, cells[index / 8] & order.mask(index) != 0);
+            }
+        }
+        // walls only carry over onto a board of the same size
+        if self.walls.len() != width || self.walls.iter().any(|walls| walls.len() != height) {
+            self.walls = vec![];
+        }
+        self.board_replaced();
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Compute-only game of `size` x `size` dead cells, with no canvas and
+    /// no frame budget: every `tick` computes a whole generation.
+    pub fn headless(size: i16) -> Game {
+        Game::headless_rect(size, size)
+    }
+    /// `headless` with `width` columns and `height` rows.
+    pub fn headless_rect(width: i16, height: i16) -> Game {
+        let (width, height) = (max(width, 1), max(height, 1));
+        let seed = rand::random();
+        let mut game = Game {
+            grid: Grid {
+                cell: 4,
+                width,
+                height,
+            },
+            rule: Rule::default(),
+            rule_regions: vec![],
+            active_region: None,
+            kernel: None,
+            birth_probability: 1.0,
+            survival_probability: 1.0,
+            update_mode: UpdateMode::default(),
+            edge_mode: EdgeMode::default(),
+            bit_order: BitOrder::default(),
+            init_mode: InitMode::default(),
+            warmup_generations: 0,
+            state: BitGrid::new(width as usize, height as usize),
+            interim_state: BitGrid::default(),
+            interim_columns: 0,
+            generation_base: BitGrid::default(),
+            last_activity: 0,
+            last_changes: vec![],
+            last_births: vec![],
+            generation_open: false,
+            generation: 0,
+            running: true,
+            breakpoint: Breakpoint::default(),
+            run_hash: 0,
+            seed,
+            rng: seeded_rng(seed),
+            out_of_bounds: OutOfBounds::default(),
+            edit_policy: EditPolicy::default(),
+            queued_edits: vec![],
+            fit_policy: FitPolicy::default(),
+            summed_area: vec![],
+            summed_area_enabled: true,
+            cache_neighbours: false,
+            neighbour_counts: vec![],
+            walls: vec![],
+            decay: vec![],
+            wall_polarity: false,
+            wall_color: "#6c7393".to_string(),
+            density_block: 1,
+            fit_canvas: None,
+            seam_margin: 0,
+            hue_speed: 0.0,
+            hue_offset: 0.0,
+            color_components: false,
+            show_ghost: false,
+            flash_color: None,
+            transparent_background: true,
+            background_color: "#ffffff".to_string(),
+            motion_blur: 0.0,
+            overlay: vec![],
+            frame_count: 0,
+            frame_width: 0,
+            frame_height: 0,
+            show_border: false,
+            border_color: "#ebb545".to_string(),
+            population_events: PopulationEvents::default(),
+            garden: Garden::default(),
+            homeostasis: Homeostasis::default(),
+            spaceships: SpaceshipTracker::default(),
+            centroid: CentroidTracker::default(),
+            period_detector: PeriodDetector::default(),
+            frame_budget_ms: DEFAULT_FRAME_BUDGET_MS,
+            governor: Governor::default(),
+            commit_times: VecDeque::new(),
+            timestep: FixedTimestep::default(),
+            background_interval_ms: 0.0,
+            pixel_ratio: 1.0,
+            #[cfg(feature = "render")]
+            canvas: None,
+            #[cfg(feature = "render")]
+            context: None,
+            #[cfg(feature = "render")]
+            drawn_state: RefCell::new(BitGrid::default()),
+            #[cfg(feature = "render")]
+            webgl: None,
+            #[cfg(feature = "render")]
+            listeners: vec![],
+            rendering_suspended: false,
+            destroyed: false,
+            log_sink: None,
+            console_logging: true,
+            performance: None,
+        };
+        game.run_hash = game.state_hash();
+        game
+    }
+    /// Advances the current generation as far as the frame budget allows,
+    /// returning whether the generation was completed and committed. Under
+    /// `set_generation_duration` it runs the generations due instead and
+    /// returns whether any did. Does nothing while paused.
+    pub fn tick(&mut self) -> bool {
+        // the clock keeps time while paused, so the pause isn't caught up on
+        if self.timestep_enabled() {
+            return self.advance_to(self.now()) > 0;
+        }
+        if !self.running {
+            return false;
+        }
+        self.govern();
+        let is_done = self.continue_generation(self.frame_budget_ms);
+        if is_done {
+            self.commit_generation();
+        }
+        is_done
+    }
+    /// Stops `tick` from advancing the game until `resume`; `step` and the
+    /// editing methods keep working.
+    pub fn pause(&mut self) {
+        self.running = false;
+    }
+    /// Lets `tick` advance the game again after `pause` or a breakpoint.
+    /// The fixed timestep clock starts over, so the pause isn't caught up
+    /// on.
+    pub fn resume(&mut self) {
+        self.running = true;
+        self.timestep.last_time = None;
+    }
+    pub fn is_paused(&self) -> bool {
+        !self.running
+    }
+    /// Completes one generation, finishing the open one if any, and draws
+    /// it, whether or not the game is paused.
+    pub fn step(&mut self) {
+        if !self.generation_open {
+            self.begin_generation();
+        }
+        self.commit_generation();
+    }
+    /// Board packed one bit per cell, row by row from the top left corner:
+    /// cell `(x, y)` is bit `y * width + x`, counting through each byte in
+    /// the `set_bit_order` order, least significant bit first by default.
+    /// This is the layout `set_cells` takes, so the buffer can be
+    /// transferred between a worker and the rendering instance.
+    pub fn cells(&self) -> Vec<u8> {
+        self.pack_cells(self.bit_order)
+    }
+    /// Replaces the board with one packed as `cells` produces it and
+    /// redraws; the buffer must be exactly `(width * height + 7) / 8` bytes.
+    /// A generation in progress is dropped.
+    pub fn set_cells(&mut self, cells: &[u8]) -> Result<(), JsValue> {
+        self.unpack_cells(cells, self.bit_order)
+    }
+    /// Order of the cells within each byte for `cells` and `set_cells`.
+    /// A tool reading the other order sees every run of 8 cells mirrored.
+    /// Snapshots from `to_bytes` always use `LsbFirst`.
+    pub fn set_bit_order(&mut self, order: BitOrder) {
+        self.bit_order = order;
+    }
+    /// Refills the board as `mode` says: `"random"`, `"empty"`, or the name
+    /// of a built-in pattern to centre on an empty board. The mode is kept
+    /// for later restarts.
+    pub fn restart(&mut self, mode: &str) -> Result<(), JsValue> {
+        let mode = match mode {
+            "random" => InitMode::Random,
+            "empty" => InitMode::Empty,
+            name => InitMode::Pattern(name.to_string()),
+        };
+        self.set_init_mode(mode)?;
+        self.start();
+        Ok(())
+    }
+    /// Generations run, without drawing, every time the board is filled on
+    /// start or `restart`, so a random board opens past its chaotic first
+    /// generations. They count towards `generation`. 0 by default.
+    pub fn set_warmup_generations(&mut self, generations: u32) {
+        self.warmup_generations = generations;
+    }
+    /// Restarts the random number generator from `seed`, making random
+    /// fills and every later random perturbation reproducible.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = seeded_rng(seed);
+    }
+    /// Position of the random number generator: its seed (u64) and how
+    /// many words it has produced (u128), little endian, 24 bytes in all.
+    pub fn rng_state(&self) -> Vec<u8> {
+        let mut state = self.seed.to_le_bytes().to_vec();
+        state.extend_from_slice(&self.rng.get_word_pos().to_le_bytes());
+        state
+    }
+    /// Restores a position saved by `rng_state`, so later random fills and
+    /// perturbations repeat exactly.
+    pub fn set_rng_state(&mut self, state: &[u8]) -> Result<(), JsValue> {
+        if state.len() != RNG_STATE_LEN {
+            return Err(format!("expected {} bytes of generator state, got {}", RNG_STATE_LEN, state.len()).into());
+        }
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&state[..8]);
+        let mut word_pos = [0u8; 16];
+        word_pos.copy_from_slice(&state[8..]);
+        self.set_seed(u64::from_le_bytes(seed));
+        self.rng.set_word_pos(u128::from_le_bytes(word_pos));
+        Ok(())
+    }
+    pub fn set_update_mode(&mut self, mode: UpdateMode) {
+        self.update_mode = mode;
+    }
+    /// Stores the board column by column (the default) or row by row, for
+    /// comparing the two; the cells and everything done with them stay the
+    /// same. A generation in progress is dropped.
+    pub fn set_layout(&mut self, layout: Layout) {
+        if layout == self.state.layout() {
+            return;
+        }
+        self.abort_generation();
+        self.state = self.state.relaid(layout);
+    }
+    /// Current rule as `{ radius, birth, survival }`, where `birth[n]` and
+    /// `survival[n]` tell whether `n` live neighbours cause a birth or keep a
+    /// cell alive. Radius 1 rules have 9 entries per array.
+    pub fn get_rule(&self) -> Result<JsValue, JsValue> {
+        let rule = js_sys::Object::new();
+        let len = self.rule.count_len();
+        js_sys::Reflect::set(&rule, &"radius".into(), &JsValue::from(self.rule.radius))?;
+        js_sys::Reflect::set(&rule, &"birth".into(), &ranges_to_array(&self.rule.birth, len))?;
+        js_sys::Reflect::set(&rule, &"survival".into(), &ranges_to_array(&self.rule.survival, len))?;
+        Ok(rule.into())
+    }
+    /// Name of the current rule, e.g. "Conway's Life", or its B/S notation
+    /// (`B3/S23`) when it is not a well known one.
+    pub fn rule_name(&self) -> String {
+        rule_name(&self.rule)
+    }
+    /// Applies a rule given as a rulestring that `Rule::parse` reads, such
+    /// as `"B36/S23"`, the isotropic `"B2-a/S12"` in Hensel notation or the
+    /// Larger than Life `"R5,C0,M0,S34..58,B34..45,NM"`,
+    /// or a radius 1 rule as `{ birth, survival }` arrays of 9 entries
+    /// (B0..B8, S0..S8). A generation in progress is restarted, and cells
+    /// dying under a previous Generations rule are dead.
+    pub fn set_rule(&mut self, rule: &JsValue) -> Result<(), JsValue> {
+        self.use_rule(match rule.as_string() {
+            Some(rule) => Rule::parse(&rule)?,
+            None => Rule {
+                radius: 1,
+                survival: array_to_ranges(rule, "survival")?,
+                birth: array_to_ranges(rule, "birth")?,
+                states: 2,
+                isotropic: None,
+            },
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // B3/S23 straight from the definition, `state[x][y]`
+    fn reference_step(state: &[Vec<bool>], wrap: bool) -> Vec<Vec<bool>> {
+        let (width, height) = (state.len() as i16, state[0].len() as i16);
+        let alive = |x: i16, y: i16| {
+            if wrap {
+                state[x.rem_euclid(width) as usize][y.rem_euclid(height) as usize]
+            } else {
+                x >= 0 && x < width && y >= 0 && y < height && state[x as usize][y as usize]
+            }
+        };
+        (0..width)
+            .map(|x| {
+                (0..height)
+                    .map(|y| {
+                        let mut neighbours = 0;
+                        for dx in -1..=1 {
+                            for dy in -1..=1 {
+                                if (dx, dy) != (0, 0) && alive(x + dx, y + dy) {
+                                    neighbours += 1;
+                                }
+                            }
+                        }
+                        neighbours == 3 || neighbours == 2 && alive(x, y)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn board(game: &Game) -> Vec<Vec<bool>> {
+        (0..game.state.width()).map(|x| (0..game.state.height()).map(|y| game.state.get(x, y)).collect()).collect()
+    }
+
+    #[test]
+    fn cells_round_trip_in_both_bit_orders() {
+        let mut rng = seeded_rng(167);
+        let state: Vec<Vec<bool>> = (0..13).map(|_| (0..7).map(|_| rng.gen_bool(0.5)).collect()).collect();
+        let game = Game::from_state(state.clone());
+        let lsb_first = game.pack_cells(BitOrder::LsbFirst);
+        for &order in &[BitOrder::LsbFirst, BitOrder::MsbFirst] {
+            let mut game = Game::from_state(state.clone());
+            game.set_bit_order(order);
+            let cells = game.cells();
+            let mut restored = Game::headless_rect(13, 7);
+            restored.set_bit_order(order);
+            restored.set_cells(&cells).unwrap();
+            assert_eq!(board(&restored), state, "{:?}", order);
+            let expected: Vec<u8> = match order {
+                BitOrder::LsbFirst => lsb_first.clone(),
+                BitOrder::MsbFirst => lsb_first.iter().map(|byte| byte.reverse_bits()).collect(),
+            };
+            assert_eq!(cells, expected, "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn set_cells_takes_a_board_from_a_worker() {
+        let mut worker = Game::headless_rect(150, 90);
+        worker.set_seed(150);
+        worker.restart("random").unwrap();
+        for _ in 0..5 {
+            worker.step();
+        }
+        let cells = worker.cells();
+        assert_eq!(cells.len(), (150 * 90) / 8 + 1);
+        let mut game = Game::headless_rect(150, 90);
+        game.begin_generation();
+        game.set_cells(&cells).unwrap();
+        assert!(!game.generation_open);
+        assert_eq!(game.state, worker.state);
+        game.step();
+        worker.step();
+        assert_eq!(game.state, worker.state);
+    }
+
+    #[test]
+    fn step_matches_the_reference() {
+        let mut rng = seeded_rng(121);
+        for &width in &[1, 5, 17, 64, 70] {
+            for &height in &[1, 2, 63, 64, 65, 130] {
+                for &(edge_mode, layout) in &[
+                    (EdgeMode::Dead, Layout::ColumnMajor),
+                    (EdgeMode::Wrap, Layout::ColumnMajor),
+                    (EdgeMode::Dead, Layout::RowMajor),
+                ] {
+                    let mut state: Vec<Vec<bool>> =
+                        (0..width).map(|_| (0..height).map(|_| rng.gen_bool(0.35)).collect()).collect();
+                    let mut game = Game::from_state(state.clone());
+                    game.set_edge_mode(edge_mode);
+                    game.set_layout(layout);
+                    for generation in 0..8 {
+                        game.step();
+                        state = reference_step(&state, edge_mode == EdgeMode::Wrap);
+                        assert_eq!(board(&game), state, "{}x{} {:?} {:?}, generation {}",
+                                   width, height, edge_mode, layout, generation + 1);
+                    }
+                }
+            }
+        }
+    }
+}
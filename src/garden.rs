@@ -0,0 +1,59 @@
+use rand::Rng;
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Periodic re-seeding of the outer band of the board, feeding fresh
+/// activity into long running displays.
+#[derive(Debug)]
+pub(crate) struct Garden {
+    pub(crate) period: u32,
+    pub(crate) depth: i16,
+    pub(crate) density: f64,
+}
+
+impl Default for Garden {
+    fn default() -> Garden {
+        Garden {
+            period: 0,
+            depth: 1,
+            density: 0.3,
+        }
+    }
+}
+
+impl Game {
+    // called once per committed generation
+    pub(crate) fn seed_garden(&mut self) {
+        let Garden { period, depth, density } = self.garden;
+        if period == 0 || !self.generation.is_multiple_of(period as u64) {
+            return;
+        }
+        self.invalidate_neighbour_cache();
+        let (width, height) = (self.grid.width, self.grid.height);
+        for col in 0..width {
+            for row in 0..height {
+                let edge_distance = col.min(row).min(width - 1 - col).min(height - 1 - row);
+                if edge_distance >= depth || self.is_wall(col as usize, row as usize) {
+                    continue;
+                }
+                let alive = self.rng.gen_bool(density);
+                self.state.set(col as usize, row as usize, alive);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Every `period` generations, re-randomizes the outer `depth` rows and
+    /// columns so that a live cell appears with probability `density`,
+    /// drawing from the seeded generator. A period of 0 turns it off.
+    pub fn set_garden(&mut self, period: u32, depth: i16, density: f64) {
+        self.garden = Garden {
+            period,
+            depth: depth.max(1),
+            density: density.clamp(0.0, 1.0),
+        };
+    }
+}
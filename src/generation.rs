@@ -0,0 +1,156 @@
+use wasm_bindgen::prelude::*;
+
+use crate::bits::BitGrid;
+use crate::game::{Game, UpdateMode};
+
+impl Game {
+    // computes columns of the open generation until the budget runs out,
+    // returning whether every column is done
+    fn calc_columns(&mut self, budget_ms: Option<f64>) -> bool {
+        let start_time: f64 = self.now();
+        for col_num in self.interim_columns..self.state.width() {
+            match self.update_mode {
+                UpdateMode::Synchronous => {
+                    self.half_tick(col_num);
+                    self.column_by_chance(col_num);
+                }
+                UpdateMode::Sequential => self.half_tick_in_place(col_num),
+            }
+            self.interim_columns += 1;
+            if let Some(budget_ms) = budget_ms {
+                if self.now() - start_time > budget_ms {
+                    break;
+                }
+            }
+        }
+        self.interim_columns == self.state.width()
+    }
+    // runs whole generations without drawing any of them, leaving the
+    // redraw to the caller
+    pub(crate) fn run_generations(&mut self, count: u32) {
+        let suspended = std::mem::replace(&mut self.rendering_suspended, true);
+        for _ in 0..count {
+            if !self.running {
+                break;
+            }
+            self.begin_generation();
+            self.commit_generation();
+        }
+        self.rendering_suspended = suspended;
+    }
+}
+
+/// A generation is computed column by column into `interim_state`, possibly
+/// across several frames, and only replaces the board once committed, by
+/// swapping the two boards.
+/// `tick` drives these steps itself; embedders doing their own scheduling
+/// can call them directly.
+#[wasm_bindgen]
+impl Game {
+    /// Opens a new generation, dropping any one in progress.
+    pub fn begin_generation(&mut self) {
+        let (width, height, layout) = (self.state.width(), self.state.height(), self.state.layout());
+        if self.interim_state.width() != width || self.interim_state.height() != height || self.interim_state.layout() != layout {
+            self.interim_state = BitGrid::with_layout(width, height, layout);
+        }
+        self.interim_columns = 0;
+        self.snapshot_generation_base();
+        if self.uses_summed_area() {
+            self.build_summed_area();
+        }
+        self.prepare_neighbour_cache();
+        self.generation_open = true;
+    }
+    /// Computes more of the open generation (opening one if needed) for
+    /// about `budget_ms` milliseconds. Returns whether it is complete and
+    /// ready to commit. Without a performance handle the budget is ignored
+    /// and the whole generation is computed.
+    pub fn continue_generation(&mut self, budget_ms: f64) -> bool {
+        if !self.generation_open {
+            self.begin_generation();
+        }
+        self.calc_columns(Some(budget_ms))
+    }
+    /// Finishes the open generation if columns remain, makes it the board
+    /// and redraws. Does nothing when no generation is open.
+    pub fn commit_generation(&mut self) {
+        if !self.generation_open {
+            return;
+        }
+        self.calc_columns(None);
+        self.advance_decay();
+        self.record_activity();
+        self.update_neighbour_cache();
+        std::mem::swap(&mut self.state, &mut self.interim_state);
+        self.interim_columns = 0;
+        self.generation_open = false;
+        self.apply_queued_edits();
+        self.generation += 1;
+        self.record_commit_time();
+        self.chain_run_hash();
+        self.hue_offset = (self.hue_offset + self.hue_speed) % 360.0;
+        self.seed_garden();
+        self.regulate_population();
+        self.track_spaceships();
+        self.track_centroid_step();
+        self.fire_population_events();
+        self.detect_period();
+        self.check_breakpoint();
+        self.redraw_changes();
+    }
+    /// Computes the whole next generation without committing it and
+    /// redraws, so the ghost overlay can show it; `commit_generation` (or
+    /// the next `tick`) then makes it the board.
+    pub fn preview_generation(&mut self) {
+        if !self.generation_open {
+            self.begin_generation();
+        }
+        self.calc_columns(None);
+        self.redraw();
+    }
+    /// Share of the open generation computed so far, from 0 to 1; 1 when no
+    /// generation is in progress.
+    pub fn generation_progress(&self) -> f64 {
+        if !self.generation_open || self.state.is_empty() {
+            return 1.0;
+        }
+        self.interim_columns as f64 / self.state.width() as f64
+    }
+    /// Value the open generation gives the cell, or `None` when its column
+    /// hasn't been computed yet, no generation is open or the cell is
+    /// outside the grid. Columns are computed left to right.
+    pub fn get_interim_cell(&self, x: i16, y: i16) -> Option<bool> {
+        if !self.generation_open || x < 0 || y < 0 {
+            return None;
+        }
+        let (col, row) = (x as usize, y as usize);
+        if col >= self.interim_columns || row >= self.interim_state.height() {
+            return None;
+        }
+        Some(self.interim_state.get(col, row))
+    }
+    /// Runs `generations` generations, rendering only after every
+    /// `render_every` of them and after the last. The canvas is only
+    /// displayed once control returns to the browser, so to watch the
+    /// skipped-ahead frames call this once per animation frame with a
+    /// `generations` of a few `render_every`.
+    pub fn fast_forward(&mut self, generations: u32, render_every: u32) {
+        let render_every = render_every.max(1);
+        let mut remaining = generations;
+        while remaining > 0 {
+            let count = remaining.min(render_every);
+            self.run_generations(count);
+            remaining -= count;
+            self.redraw();
+        }
+    }
+    /// Drops the open generation, leaving the board as it was. In
+    /// sequential mode the cells already computed were written to the
+    /// board directly and stay updated.
+    pub fn abort_generation(&mut self) {
+        self.interim_columns = 0;
+        self.generation_base = BitGrid::default();
+        self.generation_open = false;
+        self.apply_queued_edits();
+    }
+}
@@ -0,0 +1,88 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Compute budget used when no target frame rate is set.
+pub(crate) const DEFAULT_FRAME_BUDGET_MS: f64 = 13.0;
+const MIN_FRAME_BUDGET_MS: f64 = 1.0;
+// share of the frame interval error folded into the budget every frame
+const GAIN: f64 = 0.25;
+/// Commits older than this don't count towards the generation rate.
+const RATE_WINDOW_MS: f64 = 5000.0;
+const RATE_WINDOW_LEN: usize = 1024;
+
+/// Proportional controller nudging the frame budget so consecutive ticks
+/// land about one target frame interval apart.
+#[derive(Debug, Default)]
+pub(crate) struct Governor {
+    pub(crate) target_interval: Option<f64>,
+    last_frame: Option<f64>,
+}
+
+impl Game {
+    // called once per committed generation
+    pub(crate) fn record_commit_time(&mut self) {
+        if self.performance.is_none() {
+            return;
+        }
+        let now = self.now();
+        let times = &mut self.commit_times;
+        while times.front().is_some_and(|&time| now - time > RATE_WINDOW_MS) || times.len() >= RATE_WINDOW_LEN {
+            times.pop_front();
+        }
+        times.push_back(now);
+    }
+    // called at the start of every tick, i.e. once per animation frame
+    pub(crate) fn govern(&mut self) {
+        let target = match (self.governor.target_interval, &self.performance) {
+            (Some(target), Some(_)) => target,
+            _ => return,
+        };
+        let now = self.now();
+        if let Some(last) = self.governor.last_frame.replace(now) {
+            let interval = now - last;
+            // a long gap means the loop was suspended, not that we are slow
+            if interval < target * 4.0 {
+                let budget = self.frame_budget_ms + GAIN * (target - interval);
+                self.frame_budget_ms = budget.max(MIN_FRAME_BUDGET_MS).min(target);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Adapts the per-frame compute budget to keep the loop near `fps`
+    /// frames per second: slow frames shrink it, spare time grows it.
+    /// Zero or a negative value goes back to the fixed default budget.
+    pub fn set_target_fps(&mut self, fps: f64) {
+        self.governor = Governor {
+            target_interval: if fps > 0.0 { Some(1000.0 / fps) } else { None },
+            last_frame: None,
+        };
+        self.frame_budget_ms = DEFAULT_FRAME_BUDGET_MS;
+    }
+    /// Keeps the simulation running every `interval_ms` milliseconds on a
+    /// timer while the page is hidden, where browsers stop animation
+    /// frames; it goes back to animation frames once visible. 0, the
+    /// default, lets the board pause in hidden tabs.
+    pub fn set_background_interval(&mut self, interval_ms: f64) {
+        self.background_interval_ms = interval_ms.max(0.0);
+    }
+    /// Generations actually committed per second, measured between the
+    /// commits of the last five seconds (at most the last 1024), however
+    /// many frames each took or however many ran per frame. 0 until two
+    /// commits fall in the window, and always 0 in a headless game, which
+    /// has no clock.
+    pub fn true_generation_rate(&self) -> f64 {
+        let times = &self.commit_times;
+        match (times.front(), times.back()) {
+            (Some(&first), Some(&last)) if last > first => (times.len() - 1) as f64 * 1000.0 / (last - first),
+            _ => 0.0,
+        }
+    }
+    /// Milliseconds a tick currently spends computing before yielding.
+    pub fn frame_budget_ms(&self) -> f64 {
+        self.frame_budget_ms
+    }
+}
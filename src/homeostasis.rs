@@ -0,0 +1,73 @@
+use rand::Rng;
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Feedback keeping the population near a target: a shortfall is topped up
+/// by sprinkling cells, while a surplus is left to thin out by itself.
+#[derive(Debug, Default)]
+pub(crate) struct Homeostasis {
+    pub(crate) target: u32,
+    pub(crate) gain: f64,
+}
+
+impl Game {
+    // called once per committed generation
+    pub(crate) fn regulate_population(&mut self) {
+        let Homeostasis { target, gain } = self.homeostasis;
+        if target == 0 {
+            return;
+        }
+        let population = self.count_population();
+        if population >= target {
+            return;
+        }
+        let dead = (self.state.width() * self.state.height()) as u32 - population;
+        if dead > 0 {
+            self.sprinkle(gain * (target - population) as f64 / dead as f64);
+        }
+    }
+    fn sprinkle(&mut self, density: f64) {
+        let density = density.clamp(0.0, 1.0);
+        if density == 0.0 {
+            return;
+        }
+        for col in 0..self.state.width() {
+            for row in 0..self.state.height() {
+                if !self.state.get(col, row) && !self.is_wall(col, row) && self.rng.gen_bool(density) {
+                    self.state.set(col, row, true);
+                }
+            }
+        }
+        self.invalidate_neighbour_cache();
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Turns each dead cell alive with probability `density`, drawing from
+    /// the seeded generator. Walls are left alone.
+    pub fn perturb(&mut self, density: f64) {
+        self.sprinkle(density);
+        self.period_detector.reset();
+        self.redraw();
+    }
+    /// After every generation, when fewer than `target` cells are alive,
+    /// perturbs the board with a density making up `gain` times the
+    /// shortfall on average: a gain of 1 closes the gap in one step, lower
+    /// gains approach the target more gently. A target of 0 turns it off.
+    pub fn set_population_target(&mut self, target: u32, gain: f64) {
+        self.homeostasis = Homeostasis {
+            target,
+            gain: gain.max(0.0),
+        };
+    }
+    /// Target set by `set_population_target`, 0 when off.
+    pub fn population_target(&self) -> u32 {
+        self.homeostasis.target
+    }
+    /// Gain set by `set_population_target`.
+    pub fn population_gain(&self) -> f64 {
+        self.homeostasis.gain
+    }
+}
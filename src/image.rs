@@ -0,0 +1,47 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+impl Game {
+    // average Rec. 601 luma of the RGBA pixels in the given block
+    fn block_luminance(rgba: &[u8], image_width: usize, cols: (usize, usize), rows: (usize, usize)) -> f64 {
+        let mut total = 0.0;
+        for y in rows.0..rows.1 {
+            for x in cols.0..cols.1 {
+                let pixel = &rgba[(y * image_width + x) * 4..][..3];
+                total += 0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64;
+            }
+        }
+        total / ((cols.1 - cols.0) * (rows.1 - rows.0)) as f64
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Replaces the board with `data` scaled to the grid: each cell averages
+    /// the luminance of the pixels it covers and is alive when that average
+    /// is above `threshold`. Transparency is ignored. A generation in
+    /// progress is dropped.
+    pub fn seed_from_image_data(&mut self, data: &web_sys::ImageData, threshold: u8) {
+        let (cols_len, rows_len) = (self.grid.width as usize, self.grid.height as usize);
+        let (width, height) = (data.width() as usize, data.height() as usize);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let rgba = data.data();
+        // pixel span covered by cell `index` of `size`, at least one pixel wide
+        let span = |index: usize, size: usize, len: usize| {
+            let start = index * len / size;
+            (start, ((index + 1) * len / size).max(start + 1).min(len))
+        };
+        for col in 0..cols_len {
+            let cols = span(col, cols_len, width);
+            for row in 0..rows_len {
+                let rows = span(row, rows_len, height);
+                let alive = Game::block_luminance(&rgba, width, cols, rows) > threshold as f64;
+                self.state.set(col, row, alive);
+            }
+        }
+        self.board_replaced();
+    }
+}
@@ -0,0 +1,168 @@
+use crate::game::Game;
+use crate::rule::{digits_to_ranges, Rule};
+
+// neighbour bits of a configuration, in reading order around the cell
+const NEIGHBOURS: [(i16, i16); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+// Hensel letters for 1 to 4 live neighbours and a configuration for each,
+// as neighbour bits; 5 to 7 use the complements of 3 to 1.
+const LETTERS: [&str; 4] = ["ce", "ceaikn", "ceaiknjqry", "ceaiknjqrytwz"];
+const CONFIGURATIONS: [&[u8]; 4] = [
+    &[0x01, 0x02],
+    &[0x05, 0x0a, 0x03, 0x18, 0x11, 0x24],
+    &[0x25, 0x1a, 0x0b, 0x07, 0x32, 0x0d, 0x0e, 0x26, 0x19, 0x31],
+    &[0xa5, 0x5a, 0x0f, 0x1d, 0x33, 0x27, 0x3a, 0x36, 0x1b, 0x35, 0x39, 0x2e, 0x3c],
+];
+
+/// Birth and survival per neighbour configuration, for isotropic
+/// non-totalistic rules.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IsotropicTable {
+    // indexed by the neighbour bits
+    pub(crate) birth: Vec<bool>,
+    pub(crate) survival: Vec<bool>,
+    // the rule as it was written, which `Display` gives back
+    pub(crate) notation: String,
+}
+
+fn neighbour_bit(dx: i16, dy: i16) -> u8 {
+    let index = NEIGHBOURS.iter().position(|&offset| offset == (dx, dy)).unwrap();
+    1 << index
+}
+
+// the rotations and reflections of the square, as (x, y) -> (ax + by, cx + dy)
+const SYMMETRIES: [(i16, i16, i16, i16); 8] = [
+    (1, 0, 0, 1),
+    (0, -1, 1, 0),
+    (-1, 0, 0, -1),
+    (0, 1, -1, 0),
+    (-1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (1, 0, 0, -1),
+    (0, -1, -1, 0),
+];
+
+// the configuration under each symmetry
+fn symmetries(configuration: u8) -> Vec<u8> {
+    SYMMETRIES
+        .iter()
+        .map(|&(a, b, c, d)| {
+            NEIGHBOURS
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| configuration & (1 << index) != 0)
+                .fold(0, |bits, (_, &(x, y))| bits | neighbour_bit(a * x + b * y, c * x + d * y))
+        })
+        .collect()
+}
+
+// configuration of `count` live neighbours named by `letter`
+fn configuration(count: usize, letter: char) -> Option<u8> {
+    let (row, complement) = match count {
+        1..=4 => (count - 1, false),
+        5..=7 => (7 - count, true),
+        _ => return None,
+    };
+    let index = LETTERS[row].find(letter)?;
+    let configuration = CONFIGURATIONS[row][index];
+    Some(if complement { !configuration } else { configuration })
+}
+
+// Parses the counts of one half of a rule, like `2ac3` or `2-a3`, into
+// a configuration table.
+fn parse_counts(counts: &str) -> Result<Vec<bool>, String> {
+    let mut table = vec![false; 256];
+    let mut chars = counts.chars().peekable();
+    while let Some(digit) = chars.next() {
+        let count = match digit.to_digit(10) {
+            Some(count) if count <= 8 => count as usize,
+            _ => return Err(format!("invalid neighbour count {:?} in rule", digit)),
+        };
+        let negate = chars.peek() == Some(&'-');
+        if negate {
+            chars.next();
+        }
+        let mut letters = vec![];
+        while let Some(&letter) = chars.peek() {
+            if !letter.is_ascii_lowercase() {
+                break;
+            }
+            letters.push(letter);
+            chars.next();
+        }
+        let mut chosen = vec![];
+        for &letter in &letters {
+            let configuration = configuration(count, letter)
+                .ok_or_else(|| format!("no configuration {}{} in rule", count, letter))?;
+            chosen.extend(symmetries(configuration));
+        }
+        for (bits, outcome) in table.iter_mut().enumerate() {
+            if (bits as u8).count_ones() as usize != count {
+                continue;
+            }
+            let listed = chosen.contains(&(bits as u8));
+            if letters.is_empty() || listed != negate {
+                *outcome = true;
+            }
+        }
+    }
+    Ok(table)
+}
+
+// counts every one of whose configurations is in the table
+fn whole_counts(table: &[bool]) -> String {
+    (0..=8)
+        .filter(|&count| {
+            (0..256).all(|bits: usize| bits.count_ones() != count || table[bits])
+        })
+        .map(|count| std::char::from_digit(count, 10).unwrap())
+        .collect()
+}
+
+impl Rule {
+    /// Parses a rule in B/S notation with optional Hensel letters, such as
+    /// `B2ac3/S12` or `B3/S2-i34q`: a count followed by letters only
+    /// covers those configurations of that many neighbours, and after `-`
+    /// all but those. Plain totalistic rules like `B3/S23` parse too.
+    pub fn parse_isotropic(rule: &str) -> Result<Rule, String> {
+        let notation = rule.trim();
+        let upper = notation.to_ascii_uppercase();
+        let parts: Vec<&str> = upper.split('/').collect();
+        if parts.len() != 2 || !parts[0].starts_with('B') || !parts[1].starts_with('S') {
+            return Err(format!("expected B.../S... notation, got {:?}", rule));
+        }
+        let (birth, survival) = (&notation[1..parts[0].len()], &notation[parts[0].len() + 2..]);
+        if !birth.chars().chain(survival.chars()).any(|symbol| symbol.is_ascii_lowercase() || symbol == '-') {
+            return Ok(Rule {
+                birth: digits_to_ranges(birth)?,
+                survival: digits_to_ranges(survival)?,
+                ..Rule::default()
+            });
+        }
+        let table = IsotropicTable {
+            birth: parse_counts(birth)?,
+            survival: parse_counts(survival)?,
+            notation: notation.to_string(),
+        };
+        Ok(Rule {
+            birth: digits_to_ranges(&whole_counts(&table.birth))?,
+            survival: digits_to_ranges(&whole_counts(&table.survival))?,
+            isotropic: Some(table),
+            ..Rule::default()
+        })
+    }
+}
+
+impl Game {
+    // live neighbour bits around row `i`, column `j`, in `NEIGHBOURS` order
+    pub(crate) fn neighbour_configuration(&self, i: i16, j: i16) -> u8 {
+        NEIGHBOURS.iter().enumerate().fold(0, |bits, (index, &(dx, dy))| {
+            let (col, row) = (j + dx, i + dy);
+            if !self.neighbour_alive(col, row) {
+                bits
+            } else {
+                bits | 1 << index
+            }
+        })
+    }
+}
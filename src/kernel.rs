@@ -0,0 +1,54 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Square grid of neighbour weights centred on the cell, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Kernel {
+    radius: i16,
+    pub(crate) weights: Vec<u32>,
+}
+
+impl Game {
+    // sum of the weights over the live cells under the kernel centred on row `i`, column `j`
+    pub(crate) fn get_weighted_count(&self, kernel: &Kernel, i: i16, j: i16) -> u32 {
+        let radius = kernel.radius;
+        let side = 2 * radius + 1;
+        let mut count: u32 = 0;
+        for ni in (i - radius)..=(i + radius) {
+            for nj in (j - radius)..=(j + radius) {
+                if self.neighbour_alive(nj, ni) {
+                    count += kernel.weights[((ni - i + radius) * side + nj - j + radius) as usize];
+                }
+            }
+        }
+        count
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Counts neighbours as a weighted sum: `weights` is a square of odd
+    /// side, row by row, centred on the cell (whose own weight is usually
+    /// 0), and the rule's birth and survival ranges are matched against the
+    /// sum of the weights of the live cells under it. An empty array goes
+    /// back to the plain count of the rule's neighbourhood. A generation in
+    /// progress is restarted.
+    pub fn set_kernel(&mut self, weights: &[u32]) -> Result<(), JsValue> {
+        if weights.is_empty() {
+            self.kernel = None;
+            self.abort_generation();
+            return Ok(());
+        }
+        let side = (weights.len() as f64).sqrt().round() as usize;
+        if side * side != weights.len() || side.is_multiple_of(2) {
+            return Err(format!("kernel must be an odd sized square, got {} weights", weights.len()).into());
+        }
+        self.kernel = Some(Kernel {
+            radius: (side / 2) as i16,
+            weights: weights.to_vec(),
+        });
+        self.abort_generation();
+        Ok(())
+    }
+}
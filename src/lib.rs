@@ -1,189 +1,273 @@
-use std::cell::RefCell;
+#[cfg(feature = "render")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "render")]
+use std::cmp::max;
+#[cfg(feature = "render")]
 use std::rc::Rc;
+#[cfg(feature = "render")]
 use wasm_bindgen::prelude::*;
+#[cfg(feature = "render")]
 use wasm_bindgen::JsCast;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-}
+mod activity;
+mod analysis;
+mod bits;
+mod breakpoint;
+mod centroid;
+#[cfg(feature = "render")]
+mod color;
+mod config;
+mod counts;
+mod decay;
+mod diagnostics;
+mod edges;
+mod edit;
+mod events;
+mod format;
+mod game;
+mod garden;
+mod generation;
+mod governor;
+mod homeostasis;
+#[cfg(feature = "render")]
+mod image;
+mod isotropic;
+mod kernel;
+#[cfg(feature = "render")]
+mod overlay;
+mod pattern;
+mod period;
+mod region;
+#[cfg(feature = "render")]
+mod render;
+mod rule;
+mod settle;
+mod soup;
+mod spaceships;
+mod stochastic;
+mod timestep;
+mod walls;
+#[cfg(feature = "render")]
+mod webgl;
 
-macro_rules! console_log {
-    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+pub use bits::Layout;
+pub use config::Config;
+pub use edges::EdgeMode;
+pub use edit::{EditPolicy, OutOfBounds};
+pub use game::{BitOrder, Game, Grid, InitMode, UpdateMode};
+pub use pattern::{Align, FitPolicy, Pattern};
+#[cfg(feature = "render")]
+pub use render::RenderBackend;
+pub use rule::{rule_name, Rule};
+pub use settle::Settlement;
+pub use soup::SoupResult;
+
+#[cfg(feature = "render")]
+fn window() -> web_sys::Window {
+    web_sys::window().expect("no global `window` exists")
 }
 
-trait Renderable {
-    fn render(&self, _context: &web_sys::CanvasRenderingContext2d);
+#[cfg(feature = "render")]
+fn request_animation_frame(f: &Closure<dyn FnMut()>) -> i32 {
+    window()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK")
 }
 
-#[derive(Debug)]
-pub struct Grid {
-    cell: i16,
-    size: i16,
+#[cfg(feature = "render")]
+fn set_timeout(f: &Closure<dyn FnMut()>, timeout_ms: f64) -> i32 {
+    window()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(f.as_ref().unchecked_ref(), timeout_ms as i32)
+        .expect("should register `setTimeout` OK")
 }
 
-#[derive(Debug)]
-struct Game {
-    grid: Grid,
-    state: Vec<Vec<bool>>,
-    interim_state: Vec<Vec<bool>>
+/// Callback the loop is currently waiting on.
+#[cfg(feature = "render")]
+#[derive(Clone, Copy)]
+enum Pending {
+    Frame(i32),
+    Timeout(i32),
 }
 
-impl Renderable for Game {
-    fn render(&self, _context: &web_sys::CanvasRenderingContext2d) {
-        _context.clear_rect(0.0, 0.0, 1000.0, 1000.0);
-        for (col_num, col) in self.state.iter().enumerate() {
-            for row_num in 0..col.len() {
-                if col[row_num] {
-                    _context.fill_rect(
-                        col_num as f64 * self.grid.cell as f64,
-                        row_num as f64 * self.grid.cell as f64,
-                        self.grid.cell as f64,
-                        self.grid.cell as f64);
-                }
-            }
+// the animation loop's closure, set once it exists so it can schedule itself
+#[cfg(feature = "render")]
+type FrameLoop = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
+#[cfg(feature = "render")]
+fn cancel(pending: Pending) {
+    match pending {
+        Pending::Frame(handle) => {
+            let _ = window().cancel_animation_frame(handle);
         }
+        Pending::Timeout(handle) => window().clear_timeout_with_handle(handle),
     }
 }
 
-fn window() -> web_sys::Window {
-    web_sys::window().expect("no global `window` exists")
+// cells on the straight line from `from` to `to`, both included, so a fast
+// drag doesn't leave gaps
+#[cfg(feature = "render")]
+fn cells_between(from: (i16, i16), to: (i16, i16)) -> impl Iterator<Item = (i16, i16)> {
+    let steps = max((to.0 - from.0).abs(), (to.1 - from.1).abs());
+    (0..=steps).map(move |step| {
+        let along = |start: i16, end: i16| {
+            let offset = (end - start) as f64 * step as f64 / max(steps, 1) as f64;
+            start + offset.round() as i16
+        };
+        (along(from.0, to.0), along(from.1, to.1))
+    })
 }
 
-fn request_animation_frame(f: &Closure<dyn FnMut()>) {
-    window()
-        .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register `requestAnimationFrame` OK");
-}
+// Lets the mouse draw on the canvas: pressing on a dead cell brings it to
+// life and dragging paints more live cells, pressing on a live cell erases
+// the same way. The game is paused while the button is down, so the cells
+// hold still under the pointer, and releasing anywhere on the page ends
+// the stroke and lets a game that was running carry on.
+#[cfg(feature = "render")]
+fn attach_mouse(game: &Rc<RefCell<Game>>) -> Result<(), JsValue> {
+    let canvas = match &game.borrow().canvas {
+        Some(canvas) => canvas.clone(),
+        None => return Ok(()),
+    };
+    // while a button is down: what the stroke paints, the cell it last
+    // reached and whether the game was running before it
+    let stroke = Rc::new(Cell::new(None::<(bool, (i16, i16), bool)>));
 
-impl Game {
-    fn generate_row(&self) -> Vec<bool> {
-        let mut row = vec![];
-        for _i in 0..self.grid.size {
-            row.push(rand::random());
-        }
-        return row;
-    }
-    fn generate_initial_state(&self) -> Vec<Vec<bool>> {
-        let mut initial_store = vec![];
-        for _col in 0..self.grid.size {
-            initial_store.push(self.generate_row());
-        }
-        return initial_store;
-    }
-    fn start(&mut self, _context: &web_sys::CanvasRenderingContext2d) -> &mut Game {
-        self.state = self.generate_initial_state();
-        self.render(_context);
-        self
-    }
-    fn half_tick(&self, col: Vec<bool>, col_num: usize) -> Vec<bool> {
-        let mut new_state = vec![];
-        for row_num in 0..col.len() {
-            let nebour_count = self.get_nebour_count(row_num as i16, col_num as i16);
-            if col[row_num] {
-                if nebour_count < 2 {
-                    new_state.push(false);
-                } else if nebour_count > 3 {
-                    new_state.push(false);
-                } else {
-                    new_state.push(col[row_num]);
-                }
-            } else if nebour_count == 3 {
-                new_state.push(true);
-            } else {
-                new_state.push(col[row_num]);
+    let on_mouse_down = {
+        let (game, stroke) = (game.clone(), stroke.clone());
+        Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let event: web_sys::MouseEvent = event.unchecked_into();
+            let mut game = game.borrow_mut();
+            if game.destroyed {
+                return;
             }
-        }
-        new_state
-    }
-    // TODO refactor and decompose this function
-    fn calc_tick(&mut self) -> bool {
-        let perfomance = web_sys::window().unwrap().performance().expect("performance should be available");
-        let start_time: f64 = perfomance.now();
-        let mut done = true;
-        for col_num in self.interim_state.len()..self.state.len() {
-            let col = &self.state[col_num];
-            self.interim_state.push(self.half_tick(col.to_vec(), col_num));
-            let time_diff = perfomance.now() - start_time;
-            if time_diff > 13.0 {
-                done = false;
-                break;
+            if let Some(cell) = game.pixel_to_cell(event.offset_x() as f64, event.offset_y() as f64) {
+                let alive = !game.get_cell(cell.0, cell.1).unwrap_or(false);
+                let running = !game.is_paused();
+                game.pause();
+                let _ = game.set_cell(cell.0, cell.1, alive);
+                stroke.set(Some((alive, cell, running)));
             }
-        }
-        done
-    }
-    fn get_nebour_count(&self, i: i16, j: i16) -> i8 {
-        let mut count: i8 = 0;
-        for ni in (i - 1)..=(i + 1) {
-            for nj in (j - 1)..=(j + 1) {
-                if ni < 0 || ni >= self.grid.size { continue; }
-                if nj < 0 || nj >= self.grid.size { continue; }
-                if ni == i && nj == j { continue; }
-                if self.state[nj as usize][ni as usize] {
-                    count += 1;
+        }) as Box<dyn FnMut(_)>)
+    };
+    let on_mouse_move = {
+        let (game, stroke) = (game.clone(), stroke.clone());
+        Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let event: web_sys::MouseEvent = event.unchecked_into();
+            let (alive, last, running) = match stroke.get() {
+                Some(stroke) => stroke,
+                None => return,
+            };
+            let mut game = game.borrow_mut();
+            if game.destroyed {
+                return;
+            }
+            if let Some(cell) = game.pixel_to_cell(event.offset_x() as f64, event.offset_y() as f64) {
+                if cell != last {
+                    for (x, y) in cells_between(last, cell) {
+                        let _ = game.set_cell(x, y, alive);
+                    }
+                    stroke.set(Some((alive, cell, running)));
                 }
             }
+        }) as Box<dyn FnMut(_)>)
+    };
+    let on_mouse_up = {
+        let game = game.clone();
+        Closure::wrap(Box::new(move |_: web_sys::Event| {
+            if let Some((_, _, true)) = stroke.take() {
+                game.borrow_mut().resume();
+            }
+        }) as Box<dyn FnMut(_)>)
+    };
+
+    // the game removes them again when destroyed
+    let mut game = game.borrow_mut();
+    game.listen(canvas.as_ref(), "mousedown", on_mouse_down)?;
+    game.listen(canvas.as_ref(), "mousemove", on_mouse_move)?;
+    game.listen(window().as_ref(), "mouseup", on_mouse_up)
+}
+
+/// The game `start` runs, for tearing it down.
+#[cfg(feature = "render")]
+#[wasm_bindgen]
+pub struct GameHandle {
+    game: Rc<RefCell<Game>>,
+    frame_loop: FrameLoop,
+    pending: Rc<Cell<Option<Pending>>>,
+}
+
+#[cfg(feature = "render")]
+#[wasm_bindgen]
+impl GameHandle {
+    /// Destroys the game (see `Game::destroy`) and stops the loop and the
+    /// mouse drawing right away, freeing the closures `start` set up. Not
+    /// to be called from the game's own callbacks.
+    pub fn destroy(&self) {
+        self.game.borrow_mut().destroy();
+        if let Some(pending) = self.pending.take() {
+            cancel(pending);
         }
-        count
-    }
-    fn tick(&mut self, _context: &web_sys::CanvasRenderingContext2d) -> bool {
-        let is_done = self.calc_tick();
-        if is_done {
-            self.state = self.interim_state.clone();
-            self.interim_state = vec![];
-            self.render(_context);
-        }
-        true
+        self.frame_loop.borrow_mut().take();
     }
 }
 
+/// Runs a `Game::new_with_backend` game from the page's animation frames,
+/// the way the demo page does, and lets the mouse draw cells on its
+/// canvas, pausing the game for each stroke. The returned handle destroys
+/// it. Pages that drive a `Game` themselves don't call it.
+#[cfg(feature = "render")]
+#[wasm_bindgen]
+pub fn start(canvas_id: &str, width: i16, height: i16, cell_px: i16, backend: RenderBackend) -> Result<GameHandle, JsValue> {
+    let game = Game::new_with_backend(canvas_id, width, height, cell_px, backend)?;
+    let game = Rc::new(RefCell::new(game));
+    attach_mouse(&game)?;
+    let document = window().document().ok_or("window has no document")?;
 
-#[wasm_bindgen(start)]
-pub fn start() {
-    let window = web_sys::window().unwrap();
-    let document = window.document().unwrap();
-    let canvas = document.get_element_by_id("canvas").unwrap();
-    let canvas: web_sys::HtmlCanvasElement = canvas
-        .dyn_into::<web_sys::HtmlCanvasElement>()
-        .map_err(|_| ())
-        .unwrap();
-    let performance = window.performance().expect("performance should be available");
-
-    let mut game = Game {
-        grid: Grid {
-            cell: 4,
-            size: 150,
-        },
-        state: vec![],
-        interim_state: vec![]
+    // animation frames while visible, a timer while hidden if the game asks
+    // for one, and nothing once it is destroyed
+    let schedule = {
+        let game = game.clone();
+        let document = document.clone();
+        move |step: &Closure<dyn FnMut()>| {
+            let game = game.borrow();
+            if game.destroyed {
+                None
+            } else if document.hidden() && game.background_interval_ms > 0.0 {
+                Some(Pending::Timeout(set_timeout(step, game.background_interval_ms)))
+            } else {
+                Some(Pending::Frame(request_animation_frame(step)))
+            }
+        }
     };
+    let pending = Rc::new(Cell::new(None));
 
-    let context = canvas
-        .get_context("2d")
-        .unwrap()
-        .unwrap()
-        .dyn_into::<web_sys::CanvasRenderingContext2d>()
-        .unwrap();
-    &context.translate(5.0, 5.0);
-    let grid_size = game.grid.cell * game.grid.size;
-    let gradient = &context
-        .create_linear_gradient(0.0,
-                                0.0,
-                                grid_size as f64,
-                                (grid_size * 2) as f64);
-    gradient.add_color_stop(0.0, "#f8d353");
-    gradient.add_color_stop(1.0, "#f7ca98");
-    context.set_fill_style(gradient);
-
-    game.start(&context);
-
-    let f = Rc::new(RefCell::new(None));
+    let f: FrameLoop = Rc::new(RefCell::new(None));
     let g = f.clone();
+    {
+        let (game, schedule, pending) = (game.clone(), schedule.clone(), pending.clone());
+        *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+            pending.set(schedule(f.borrow().as_ref().unwrap()));
+            let idle = {
+                let game = game.borrow();
+                game.destroyed || game.is_paused()
+            };
+            if !idle {
+                game.borrow_mut().tick();
+            }
+        }) as Box<dyn FnMut()>));
+    }
+    pending.set(schedule(g.borrow().as_ref().unwrap()));
 
-    *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
-        request_animation_frame(f.borrow().as_ref().unwrap());
-        game.tick(&context);
-    }) as Box<dyn FnMut()>));
-    request_animation_frame(g.borrow().as_ref().unwrap());
+    // switch drivers right away: a hidden page never runs the pending frame
+    let on_visibility_change = {
+        let (frame_loop, pending) = (g.clone(), pending.clone());
+        Closure::wrap(Box::new(move |_: web_sys::Event| {
+            if let Some(previous) = pending.take() {
+                cancel(previous);
+            }
+            pending.set(schedule(frame_loop.borrow().as_ref().unwrap()));
+        }) as Box<dyn FnMut(_)>)
+    };
+    game.borrow_mut().listen(document.as_ref(), "visibilitychange", on_visibility_change)?;
+    Ok(GameHandle { game, frame_loop: g, pending })
 }
@@ -0,0 +1,46 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+impl Game {
+    // markers go on top of everything `render` drew, at the board's cell
+    // pitch so they follow `fit_to_canvas`
+    pub(crate) fn draw_overlay(&self, context: &web_sys::CanvasRenderingContext2d) {
+        if self.overlay.is_empty() {
+            return;
+        }
+        let pitch = self.cell_pitch();
+        let fill_style = context.fill_style();
+        for (x, y, color) in &self.overlay {
+            context.set_fill_style(&JsValue::from_str(color));
+            context.fill_rect(*x as f64 * pitch, *y as f64 * pitch, pitch, pitch);
+        }
+        context.set_fill_style(&fill_style);
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Marks cell `(x, y)` with `color` on the overlay, replacing any marker
+    /// already there. The overlay is drawn after the board and is never
+    /// read by the simulation; markers outside the board are ignored.
+    pub fn add_overlay_marker(&mut self, x: i16, y: i16, color: &str) {
+        if !self.grid.contains(x, y) {
+            return;
+        }
+        self.remove_overlay_marker(x, y);
+        self.overlay.push((x, y, color.to_string()));
+    }
+    /// Removes the overlay marker at `(x, y)`, if any.
+    pub fn remove_overlay_marker(&mut self, x: i16, y: i16) {
+        self.overlay.retain(|&(mx, my, _)| mx != x || my != y);
+    }
+    /// Removes every overlay marker.
+    pub fn clear_overlay(&mut self) {
+        self.overlay.clear();
+    }
+    /// Number of overlay markers.
+    pub fn overlay_len(&self) -> usize {
+        self.overlay.len()
+    }
+}
@@ -0,0 +1,296 @@
+use std::cmp::max;
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+use crate::rule::Rule;
+
+/// Live cells of a pattern, relative to the top left corner of its
+/// bounding box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub(crate) width: i16,
+    pub(crate) height: i16,
+    pub(crate) cells: Vec<(i16, i16)>,
+}
+
+impl Pattern {
+    /// Builds a pattern from `(x, y)` live cells, shifting them so the
+    /// bounding box starts at the origin.
+    pub fn new(cells: Vec<(i16, i16)>) -> Pattern {
+        let left = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let top = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let cells: Vec<(i16, i16)> = cells.iter().map(|&(x, y)| (x - left, y - top)).collect();
+        Pattern {
+            width: cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0),
+            height: cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0),
+            cells,
+        }
+    }
+    /// One of the built-in patterns, by its usual name: `block`, `beehive`,
+    /// `blinker`, `glider`, `lwss`, `eater`, `r-pentomino`, `acorn`,
+    /// `diehard` or `gosper-gun`.
+    pub fn named(name: &str) -> Option<Pattern> {
+        let cells: &[(i16, i16)] = match name {
+            "block" => &[(0, 0), (1, 0), (0, 1), (1, 1)],
+            "beehive" => &[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)],
+            "blinker" => &[(0, 0), (1, 0), (2, 0)],
+            "glider" => &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)],
+            "lwss" => &[(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)],
+            "eater" => &[(0, 0), (1, 0), (0, 1), (2, 1), (2, 2), (2, 3), (3, 3)],
+            "r-pentomino" => &[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)],
+            "acorn" => &[(1, 0), (3, 1), (0, 2), (1, 2), (4, 2), (5, 2), (6, 2)],
+            "diehard" => &[(6, 0), (0, 1), (1, 1), (1, 2), (5, 2), (6, 2), (7, 2)],
+            "gosper-gun" => &[
+                (0, 4), (0, 5), (1, 4), (1, 5),
+                (10, 4), (10, 5), (10, 6), (11, 3), (11, 7), (12, 2), (12, 8), (13, 2), (13, 8),
+                (14, 5), (15, 3), (15, 7), (16, 4), (16, 5), (16, 6), (17, 5),
+                (20, 2), (20, 3), (20, 4), (21, 2), (21, 3), (21, 4), (22, 1), (22, 5),
+                (24, 0), (24, 1), (24, 5), (24, 6),
+                (34, 2), (34, 3), (35, 2), (35, 3),
+            ],
+            _ => return None,
+        };
+        Some(Pattern::new(cells.to_vec()))
+    }
+    /// Run-length encoded pattern file: an `x = .., y = .., rule = ..`
+    /// header, then the rows as runs of `b` (dead) and `o` (alive) ended by
+    /// `$`, trailing dead cells left out, and `!` at the end. Lines are
+    /// wrapped at 70 characters.
+    pub fn to_rle(&self, rule: &Rule) -> String {
+        let mut alive = vec![vec![false; self.width as usize]; self.height as usize];
+        for &(x, y) in &self.cells {
+            alive[y as usize][x as usize] = true;
+        }
+        // (count, tag) runs, with the end of each row as a `$` run
+        let mut runs: Vec<(usize, char)> = vec![];
+        let push = |runs: &mut Vec<(usize, char)>, tag: char| match runs.last_mut() {
+            Some((run, last)) if *last == tag => *run += 1,
+            _ => runs.push((1, tag)),
+        };
+        for (row_num, row) in alive.iter().enumerate() {
+            let len = row.iter().rposition(|&alive| alive).map_or(0, |last| last + 1);
+            for &cell in &row[..len] {
+                push(&mut runs, if cell { 'o' } else { 'b' });
+            }
+            if row_num + 1 < alive.len() {
+                push(&mut runs, '$');
+            }
+        }
+
+        let mut rle = format!("x = {}, y = {}, rule = {}\n", self.width, self.height, rule);
+        let mut line_len = 0;
+        for (count, tag) in runs.iter().map(|&(count, tag)| (count, tag.to_string())).chain(Some((1, "!".to_string()))) {
+            let token = if count > 1 { format!("{}{}", count, tag) } else { tag };
+            if line_len + token.len() > 70 {
+                rle.push('\n');
+                line_len = 0;
+            }
+            line_len += token.len();
+            rle.push_str(&token);
+        }
+        rle.push('\n');
+        rle
+    }
+    /// Plaintext (`.cells`) pattern file: one line per row with `O` for
+    /// alive and `.` for dead, as LifeWiki lists patterns.
+    pub fn to_plaintext(&self) -> String {
+        let mut rows = vec![vec!['.'; self.width as usize]; self.height as usize];
+        for &(x, y) in &self.cells {
+            rows[y as usize][x as usize] = 'O';
+        }
+        rows.into_iter().map(|row| row.into_iter().collect::<String>() + "\n").collect()
+    }
+    /// Reads a run-length encoded pattern file as Golly and LifeWiki write
+    /// them: `#` comment lines, an optional `x = .., y = ..` header, then
+    /// runs of `b` (dead) and `o` (alive) with `$` ending a row, up to
+    /// `!`. Other letters count as alive, as multi-state files use them
+    /// for live states, and `.` as dead. The header's rule is not applied.
+    pub fn from_rle(rle: &str) -> Result<Pattern, String> {
+        let mut header = (0, 0);
+        let mut cells = vec![];
+        let (mut x, mut y, mut count) = (0i16, 0i16, None::<i16>);
+        'lines: for line in rle.lines().map(str::trim) {
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if line.starts_with('x') {
+                for (key, value) in line.split(',').filter_map(|field| {
+                    let mut field = field.splitn(2, '=');
+                    Some((field.next()?.trim(), field.next()?.trim()))
+                }) {
+                    let size = || value.parse::<i16>().map_err(|_| format!("invalid {} = {:?} in RLE header", key, value));
+                    match key {
+                        "x" => header.0 = size()?,
+                        "y" => header.1 = size()?,
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+            for tag in line.chars().filter(|tag| !tag.is_whitespace()) {
+                if let Some(digit) = tag.to_digit(10) {
+                    let run = count.unwrap_or(0).checked_mul(10).and_then(|run| run.checked_add(digit as i16));
+                    count = Some(run.ok_or("run length too long in RLE")?);
+                    continue;
+                }
+                let run = count.take().unwrap_or(1);
+                let skip = |position: i16| position.checked_add(run).ok_or("pattern too large in RLE");
+                match tag {
+                    'b' | '.' => x = skip(x)?,
+                    '$' => {
+                        x = 0;
+                        y = skip(y)?;
+                    }
+                    '!' => break 'lines,
+                    tag if tag.is_ascii_alphabetic() => {
+                        let end = skip(x)?;
+                        cells.extend((x..end).map(|col| (col, y)));
+                        x = end;
+                    }
+                    tag => return Err(format!("unexpected {:?} in RLE", tag)),
+                }
+            }
+        }
+        Ok(Pattern {
+            width: cells.iter().map(|&(x, _)| x + 1).fold(header.0, max),
+            height: cells.iter().map(|&(_, y)| y + 1).fold(header.1, max),
+            cells,
+        })
+    }
+}
+
+/// Where a pattern's bounding box lands on the board.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    /// Bounding box centred on the grid.
+    Center,
+    /// Bounding box in the top left corner of the grid.
+    TopLeft,
+    /// Top left corner of the bounding box at the given cell.
+    At(i16, i16),
+}
+
+/// What pattern loaders do with a pattern that doesn't fit on the grid.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FitPolicy {
+    /// Cells falling outside the grid are dropped.
+    #[default]
+    Clip,
+    /// The grid (and canvas) grows to hold the whole pattern first. A big
+    /// pattern can make the board, and the cost of every generation and
+    /// frame, much larger.
+    GrowGrid,
+}
+
+impl Game {
+    /// The board's live cells cropped to their bounding box. Cells dying
+    /// under a Generations rule are left out.
+    pub fn to_pattern(&self) -> Pattern {
+        Pattern::new(self.iter_live().collect())
+    }
+    // grows the grid so the pattern lands on it whole, if the policy says so
+    fn fit_pattern(&mut self, pattern: &Pattern, align: Align) {
+        if self.fit_policy != FitPolicy::GrowGrid {
+            return;
+        }
+        let (width, height) = match align {
+            Align::Center | Align::TopLeft => (pattern.width, pattern.height),
+            Align::At(x, y) => (x + pattern.width, y + pattern.height),
+        };
+        if width > self.grid.width || height > self.grid.height {
+            self.resize(max(width, self.grid.width), max(height, self.grid.height));
+        }
+    }
+    fn pattern_origin(&self, pattern: &Pattern, align: Align) -> (i16, i16) {
+        match align {
+            Align::Center => (
+                max(self.grid.width - pattern.width, 0) / 2,
+                max(self.grid.height - pattern.height, 0) / 2,
+            ),
+            Align::TopLeft => (0, 0),
+            Align::At(x, y) => (x, y),
+        }
+    }
+    // turns the cells on as `set_cell` would, so walls keep their state
+    pub(crate) fn stamp_pattern(&mut self, pattern: &Pattern, align: Align) {
+        let (left, top) = self.pattern_origin(pattern, align);
+        for &(x, y) in &pattern.cells {
+            let (col, row) = (left.saturating_add(x), top.saturating_add(y));
+            if !self.grid.contains(col, row) {
+                continue;
+            }
+            self.write_cell(col as usize, row as usize, true);
+        }
+    }
+    /// Turns on the pattern's cells at the given alignment, leaving the rest
+    /// of the board as it is. Cells falling outside the grid are dropped,
+    /// or the grid grows to hold them under `FitPolicy::GrowGrid`. Walls
+    /// keep their state. A generation in progress is restarted, and the
+    /// run hash starts over from the new board.
+    pub fn place_pattern(&mut self, pattern: &Pattern, align: Align) {
+        // columns computed from the old board would drop the new cells
+        self.abort_generation();
+        self.fit_pattern(pattern, align);
+        self.stamp_pattern(pattern, align);
+        self.period_detector.reset();
+        self.run_hash = self.state_hash();
+        self.redraw();
+    }
+    /// Clears the board and places each `(name, x, y)` built-in pattern
+    /// (see `Pattern::named`) with its top left corner at `(x, y)`.
+    /// Overlapping patterns combine, and the fit policy applies as in
+    /// `place_pattern`. An unknown name fails before anything
+    /// changes.
+    pub fn place_scene(&mut self, placements: &[(String, i16, i16)]) -> Result<(), String> {
+        let mut patterns = vec![];
+        for (index, (name, x, y)) in placements.iter().enumerate() {
+            let pattern = Pattern::named(name)
+                .ok_or_else(|| format!("unknown pattern '{}' in placement {}", name, index))?;
+            patterns.push((pattern, Align::At(*x, *y)));
+        }
+        self.abort_generation();
+        for (pattern, align) in &patterns {
+            self.fit_pattern(pattern, *align);
+        }
+        self.state.clear();
+        for (pattern, align) in &patterns {
+            self.stamp_pattern(pattern, *align);
+        }
+        self.board_replaced();
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// How pattern loaders treat patterns larger than the grid; `Clip` by
+    /// default.
+    pub fn set_fit_policy(&mut self, policy: FitPolicy) {
+        self.fit_policy = policy;
+    }
+    /// RLE of a built-in pattern (see `Pattern::named`) under Conway's
+    /// rule, for previewing it without touching the board, or `None` for an
+    /// unknown name.
+    pub fn pattern_rle(&self, name: &str) -> Option<String> {
+        Pattern::named(name).map(|pattern| pattern.to_rle(&Rule::default()))
+    }
+    /// The board's live cells, cropped to their bounding box, as an RLE
+    /// file (see `Pattern::to_rle`) under the board rule.
+    pub fn export_rle(&self) -> String {
+        self.to_pattern().to_rle(&self.rule)
+    }
+    /// The board's live cells, cropped to their bounding box, as a
+    /// plaintext file (see `Pattern::to_plaintext`).
+    pub fn export_plaintext(&self) -> String {
+        self.to_pattern().to_plaintext()
+    }
+    /// Turns on the cells of an RLE pattern (see `Pattern::from_rle`) with
+    /// the top left corner of its bounding box at `(x, y)`, as
+    /// `place_pattern` does. Nothing changes if the RLE doesn't parse.
+    pub fn load_rle(&mut self, pattern: &str, x: i16, y: i16) -> Result<(), JsValue> {
+        let pattern = Pattern::from_rle(pattern)?;
+        self.place_pattern(&pattern, Align::At(x, y));
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+use crate::game::{BitOrder, Game};
+
+/// 64-bit FNV-1a, a fixed algorithm so hashes match across platforms.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Longest cycle looked for, whatever `set_max_period` asks, so the history
+/// of packed boards stays bounded.
+const PERIOD_LIMIT: u32 = 1024;
+
+/// Remembers the last `max_period` boards and reports how many
+/// generations back the current one last occurred.
+#[derive(Debug, Default)]
+pub(crate) struct PeriodDetector {
+    max_period: usize,
+    // hash and packed cells of each recent generation, oldest first
+    history: VecDeque<(u64, Vec<u8>)>,
+    period: Option<u32>,
+}
+
+impl PeriodDetector {
+    pub(crate) fn new(max_period: u32) -> PeriodDetector {
+        PeriodDetector {
+            max_period: max_period as usize,
+            ..PeriodDetector::default()
+        }
+    }
+    pub(crate) fn max_period(&self) -> u32 {
+        self.max_period as u32
+    }
+    pub(crate) fn period(&self) -> Option<u32> {
+        self.period
+    }
+    pub(crate) fn reset(&mut self) {
+        self.history.clear();
+        self.period = None;
+    }
+    pub(crate) fn observe(&mut self, cells: Vec<u8>) {
+        let hash = fnv1a(&cells);
+        // the hash only narrows the search; the boards are compared in full
+        self.period = self.history
+            .iter()
+            .rev()
+            .position(|(other_hash, other)| *other_hash == hash && *other == cells)
+            .map(|back| back as u32 + 1);
+        self.history.push_back((hash, cells));
+        if self.history.len() > self.max_period {
+            self.history.pop_front();
+        }
+    }
+}
+
+impl Game {
+    // called once per committed generation
+    pub(crate) fn chain_run_hash(&mut self) {
+        let mut bytes = self.run_hash.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&self.state_hash().to_le_bytes());
+        self.run_hash = fnv1a(&bytes);
+    }
+    // called once per committed generation
+    pub(crate) fn detect_period(&mut self) {
+        if self.period_detector.max_period == 0 {
+            return;
+        }
+        let cells = self.pack_cells(BitOrder::LsbFirst);
+        self.period_detector.observe(cells);
+    }
+    // compute-only game with this board and the rules it evolves under;
+    // the garden, population target and callbacks stay behind
+    pub(crate) fn headless_copy(&self) -> Game {
+        let mut copy = Game::headless_rect(self.grid.width, self.grid.height);
+        copy.rule = self.rule.clone();
+        copy.rule_regions = self.rule_regions.clone();
+        copy.active_region = self.active_region;
+        copy.kernel = self.kernel.clone();
+        copy.birth_probability = self.birth_probability;
+        copy.survival_probability = self.survival_probability;
+        copy.update_mode = self.update_mode;
+        copy.edge_mode = self.edge_mode;
+        copy.state = self.state.clone();
+        copy.walls = self.walls.clone();
+        copy.wall_polarity = self.wall_polarity;
+        copy.decay = self.decay.clone();
+        copy.rng = self.rng.clone();
+        copy
+    }
+    // the hash only narrows the comparison; the boards are compared in full
+    fn same_state(&self, other: &Game) -> bool {
+        self.state_hash() == other.state_hash() && self.state == other.state && self.decay == other.decay
+    }
+    /// `(preperiod, period)` of the current board: it first repeats after
+    /// `preperiod` generations, every `period` generations from then on.
+    /// Runs Floyd's tortoise and hare on headless copies, so the game is
+    /// untouched and only two boards are kept whatever the period. Finds
+    /// every cycle with `preperiod + period <= max` and returns `None`
+    /// when the hare has run `2 * max` generations without meeting the
+    /// tortoise, or when the preperiod or period walked from the meeting
+    /// point passes `max`, as it can when birth or survival is left to
+    /// chance. A spaceship only repeats under `EdgeMode::Wrap`.
+    pub fn find_period(&self, max: u32) -> Option<(u32, u32)> {
+        let mut tortoise = self.headless_copy();
+        let mut hare = self.headless_copy();
+        let mut met = false;
+        for _ in 0..max {
+            tortoise.step();
+            hare.step();
+            hare.step();
+            if tortoise.same_state(&hare) {
+                met = true;
+                break;
+            }
+        }
+        if !met {
+            return None;
+        }
+        // the meeting point is a multiple of the period past the start of
+        // the cycle, so walking from there and from the start meets on it
+        let mut start = self.headless_copy();
+        let mut preperiod = 0;
+        while !start.same_state(&tortoise) {
+            if preperiod == max {
+                return None;
+            }
+            start.step();
+            tortoise.step();
+            preperiod += 1;
+        }
+        let mut period = 1;
+        hare = start.headless_copy();
+        hare.step();
+        while !hare.same_state(&start) {
+            if period == max {
+                return None;
+            }
+            hare.step();
+            period += 1;
+        }
+        Some((preperiod, period))
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// `find_period` for JS, as `[preperiod, period]`, or an empty array
+    /// when no cycle was found.
+    #[wasm_bindgen(js_name = find_period)]
+    pub fn find_period_js(&self, max: u32) -> Vec<u32> {
+        self.find_period(max).map_or(vec![], |(preperiod, period)| vec![preperiod, period])
+    }
+    /// Looks for cycles of up to `max_period` generations (at most 1024);
+    /// 0 turns the detection off. Keeps one packed board per generation of
+    /// the window.
+    pub fn set_max_period(&mut self, max_period: u32) {
+        self.period_detector.max_period = max_period.min(PERIOD_LIMIT) as usize;
+        self.period_detector.reset();
+    }
+    /// Generations since the current board last occurred, if that was
+    /// within `max_period`: 1 for a still life, 2 for a blinker and so on.
+    pub fn detected_period(&self) -> Option<u32> {
+        self.period_detector.period
+    }
+    /// FNV-1a hash of the board packed as `cells` packs it least
+    /// significant bit first, identical on every platform for the same
+    /// board.
+    pub fn state_hash(&self) -> u64 {
+        fnv1a(&self.pack_cells(BitOrder::LsbFirst))
+    }
+    /// Fingerprint of the whole run: starts as the `state_hash` of the
+    /// board it was given and folds in the `state_hash` of every committed
+    /// generation. A seeded run therefore ends on the same value everywhere.
+    pub fn run_hash(&self) -> u64 {
+        self.run_hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_stays_within_the_limit() {
+        let mut game = Game::headless(16);
+        game.set_seed(128);
+        game.restart("random").unwrap();
+        game.set_max_period(u32::MAX);
+        for _ in 0..100_000 {
+            game.step();
+            assert!(game.period_detector.history.len() <= PERIOD_LIMIT as usize);
+        }
+        assert_eq!(game.period_detector.history.len(), PERIOD_LIMIT as usize);
+    }
+
+    #[test]
+    fn find_period_stops_at_max() {
+        let mut game = Game::headless(8);
+        for x in 1..4 {
+            game.state.set(x, 2, true);
+        }
+        assert_eq!(game.find_period(10), Some((0, 2)));
+        // a chance birth or death can make the two meet on a board that
+        // doesn't repeat
+        game.set_seed(183);
+        game.restart("random").unwrap();
+        game.set_birth_probability(0.5);
+        game.set_survival_probability(0.5);
+        for max in 0..20 {
+            if let Some((preperiod, period)) = game.find_period(max) {
+                assert!(preperiod <= max && period <= max);
+            }
+        }
+    }
+}
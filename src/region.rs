@@ -0,0 +1,115 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+use crate::rule::{array_to_ranges, Rule};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rect {
+    pub(crate) x: i16,
+    pub(crate) y: i16,
+    pub(crate) width: i16,
+    pub(crate) height: i16,
+}
+
+impl Rect {
+    pub(crate) fn contains(&self, col: i16, row: i16) -> bool {
+        col >= self.x && col - self.x < self.width && row >= self.y && row - self.y < self.height
+    }
+}
+
+impl Game {
+    // the latest region covering the cell wins, the board rule applies elsewhere
+    pub(crate) fn rule_at(&self, col: usize, row: usize) -> &Rule {
+        self.rule_regions
+            .iter()
+            .rev()
+            .find(|(rect, _)| rect.contains(col as i16, row as i16))
+            .map_or(&self.rule, |(_, rule)| rule)
+    }
+    // cells outside the active region keep their state, like walls
+    pub(crate) fn is_frozen(&self, col: usize, row: usize) -> bool {
+        self.active_region.is_some_and(|rect| !rect.contains(col as i16, row as i16))
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Governs the `width` x `height` rectangle at (`x`, `y`) by a radius 1
+    /// rule given as `{ birth, survival }` arrays the way `set_rule` takes
+    /// them, on top of earlier regions. Cells follow the rule of their own
+    /// region but count neighbours across region boundaries as usual. A
+    /// generation in progress is restarted.
+    pub fn add_rule_region(&mut self, x: i16, y: i16, width: i16, height: i16, rule: &JsValue) -> Result<(), JsValue> {
+        let rule = Rule {
+            radius: 1,
+            survival: array_to_ranges(rule, "survival")?,
+            birth: array_to_ranges(rule, "birth")?,
+            states: 2,
+            isotropic: None,
+        };
+        self.rule_regions.push((Rect { x, y, width, height }, rule));
+        self.abort_generation();
+        Ok(())
+    }
+    /// Drops every rule region, leaving the whole board to the board rule.
+    pub fn clear_rule_regions(&mut self) {
+        self.rule_regions = vec![];
+        self.abort_generation();
+    }
+    /// Evolves only the `width` x `height` rectangle at (`x`, `y`); the
+    /// cells outside it stay as they are, a frozen backdrop the region's
+    /// cells still count as neighbours. A generation in progress is
+    /// restarted.
+    pub fn set_active_region(&mut self, x: i16, y: i16, width: i16, height: i16) {
+        self.active_region = Some(Rect { x, y, width, height });
+        self.abort_generation();
+    }
+    /// Lets the whole board evolve again.
+    pub fn clear_active_region(&mut self) {
+        self.active_region = None;
+        self.abort_generation();
+    }
+    /// Kills every cell outside the active region, leaving the part that
+    /// evolves and the walls alone, and redraws. Does nothing without an
+    /// active region.
+    pub fn clear_outside_region(&mut self) {
+        if self.active_region.is_none() {
+            return;
+        }
+        let outside: Vec<(usize, usize)> = self.state.live_cells().filter(|&(col, row)| self.is_frozen(col, row)).collect();
+        for (col, row) in outside {
+            self.write_cell(col, row, false);
+        }
+        self.period_detector.reset();
+        self.redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::Game;
+
+    #[test]
+    fn only_the_active_region_evolves() {
+        let mut game = Game::headless(12);
+        // a blinker on each side of the region's edge
+        for x in 1..4 {
+            game.state.set(x, 2, true);
+            game.state.set(x + 7, 2, true);
+        }
+        game.set_active_region(6, 0, 6, 12);
+        game.tick();
+        assert!((1..4).all(|x| game.state.get(x, 2)));
+        assert!((1..4).all(|y| game.state.get(9, y)));
+        game.clear_outside_region();
+        assert_eq!(game.state.live_cells().collect::<Vec<_>>(), vec![(9, 1), (9, 2), (9, 3)]);
+    }
+
+    #[test]
+    fn clearing_without_an_active_region_keeps_the_board() {
+        let mut game = Game::headless(4);
+        game.state.set(1, 1, true);
+        game.clear_outside_region();
+        assert!(game.state.get(1, 1));
+    }
+}
@@ -0,0 +1,720 @@
+use std::cmp::{max, min};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::bits::BitGrid;
+use crate::breakpoint::Breakpoint;
+use crate::color::hsl_to_hex;
+use crate::game::Game;
+use crate::webgl::WebGlRenderer;
+
+/// Gap, in CSS pixels, between the canvas edge and the board.
+pub(crate) const CANVAS_PADDING: f64 = 5.0;
+
+/// Overlay colors for cells the pending generation gives birth to or kills.
+const GHOST_BIRTH_COLOR: &str = "#3fb950";
+const GHOST_DEATH_COLOR: &str = "#f85149";
+
+/// Fill gradient stops as (offset, hue, saturation, lightness); at a hue
+/// offset of 0 they are #f8d353 and #f7ca98.
+pub(crate) const GRADIENT: [(f32, f64, f64, f64); 2] = [
+    (0.0, 46.55, 0.9218, 0.649),
+    (1.0, 31.58, 0.8559, 0.7824),
+];
+
+// an event listener registered for the game; the closure has to live as
+// long as the registration
+#[derive(Debug)]
+pub(crate) struct Listener {
+    target: web_sys::EventTarget,
+    event: &'static str,
+    closure: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+/// Which renderer a game draws its canvas with, chosen when it is created.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderBackend {
+    /// `fill_rect` per cell on a 2d canvas, with every styling option.
+    Canvas2d,
+    /// A texture of the cells drawn by WebGL, for boards of hundreds of
+    /// thousands of cells. Only draws live cells on the background.
+    WebGl,
+}
+
+/// Something that draws a game's board onto its canvas.
+pub(crate) trait Renderable {
+    fn render(&self, game: &Game);
+}
+
+impl Renderable for web_sys::CanvasRenderingContext2d {
+    fn render(&self, game: &Game) {
+        if game.hue_speed != 0.0 {
+            // the stops are always valid colors and offsets
+            let _ = game.apply_gradient(self);
+        }
+        game.clear(self);
+        game.draw_board(self);
+        game.draw_overlay(self);
+    }
+}
+
+impl Game {
+    // registers `closure` for `event` on `target` until the game is destroyed
+    pub(crate) fn listen(
+        &mut self,
+        target: &web_sys::EventTarget,
+        event: &'static str,
+        closure: Closure<dyn FnMut(web_sys::Event)>,
+    ) -> Result<(), JsValue> {
+        target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())?;
+        self.listeners.push(Listener { target: target.clone(), event, closure });
+        Ok(())
+    }
+    // everything `render` draws after clearing, in the context's current fill style
+    fn draw_board(&self, _context: &web_sys::CanvasRenderingContext2d) {
+        if let Some((width, height)) = self.fit_canvas {
+            self.draw_fitted(_context, width, height);
+            return;
+        }
+        if self.density_block > 1 {
+            self.draw_density(_context, self.density_block);
+            self.draw_border(_context);
+            return;
+        }
+        if self.color_components {
+            self.draw_components(_context);
+        } else {
+            for (col_num, row_num) in self.state.live_cells() {
+                _context.fill_rect(
+                    col_num as f64 * self.grid.cell as f64,
+                    row_num as f64 * self.grid.cell as f64,
+                    self.grid.cell as f64,
+                    self.grid.cell as f64);
+            }
+        }
+        if let Some(color) = &self.flash_color {
+            self.fill_cells(_context, color, &self.last_births);
+        }
+        self.draw_decay(_context);
+        self.draw_seam_margin(_context);
+        self.draw_walls(_context);
+        self.draw_ghost(_context);
+        self.draw_border(_context);
+    }
+    // the board comes from `headless_rect`, dead, with the canvas sized to it
+    fn with_canvas(canvas_id: &str, width: i16, height: i16, cell_px: i16, backend: RenderBackend) -> Result<Game, JsValue> {
+        let window = web_sys::window().ok_or("no global `window` exists")?;
+        let document = window.document().ok_or("window has no document")?;
+        let canvas = document
+            .get_element_by_id(canvas_id)
+            .ok_or_else(|| format!("canvas element '{}' not found", canvas_id))?
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .map_err(|_| format!("element '{}' is not a canvas", canvas_id))?;
+        let performance = window.performance().ok_or("performance unavailable")?;
+        let (context, webgl) = match backend {
+            RenderBackend::Canvas2d => {
+                let context = canvas
+                    .get_context("2d")?
+                    .ok_or("2d context unavailable")?
+                    .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+                (Some(context), None)
+            }
+            RenderBackend::WebGl => (None, Some(WebGlRenderer::new(&canvas)?)),
+        };
+
+        let mut game = Game {
+            canvas: Some(canvas),
+            context,
+            webgl,
+            performance: Some(performance),
+            ..Game::headless_rect(width, height)
+        };
+        game.grid.cell = max(cell_px, 1);
+        game.size_canvas()?;
+        Ok(game)
+    }
+    // Sets the canvas attribute size to the board plus padding times the
+    // pixel ratio, which also resets the context, so the transform and the
+    // gradient are set up again. CSS pixels stay the unit everything is
+    // drawn in.
+    pub(crate) fn size_canvas(&self) -> Result<(), JsValue> {
+        let canvas = match &self.canvas {
+            Some(canvas) => canvas,
+            None => return Ok(()),
+        };
+        let (css_width, css_height) = self.canvas_css_size();
+        canvas.set_width((css_width * self.pixel_ratio).round() as u32);
+        canvas.set_height((css_height * self.pixel_ratio).round() as u32);
+        self.forget_drawn_state();
+        // WebGL places the board itself every frame
+        let context = match &self.context {
+            Some(context) => context,
+            None => return Ok(()),
+        };
+        context.scale(self.pixel_ratio, self.pixel_ratio)?;
+        context.translate(CANVAS_PADDING, CANVAS_PADDING)?;
+        self.apply_gradient(context)
+    }
+    // board plus padding on every side, in CSS pixels
+    fn canvas_css_size(&self) -> (f64, f64) {
+        let side = |cells: i16| (self.grid.cell * cells) as f64 + 2.0 * CANVAS_PADDING;
+        (side(self.grid.width), side(self.grid.height))
+    }
+    fn apply_gradient(&self, context: &web_sys::CanvasRenderingContext2d) -> Result<(), JsValue> {
+        let gradient = context
+            .create_linear_gradient(0.0,
+                                    0.0,
+                                    (self.grid.cell * self.grid.width) as f64,
+                                    (self.grid.cell * self.grid.height * 2) as f64);
+        for &(offset, hue, saturation, lightness) in GRADIENT.iter() {
+            gradient.add_color_stop(offset, &hsl_to_hex(hue + self.hue_offset, saturation, lightness))?;
+        }
+        context.set_fill_style(&gradient);
+        Ok(())
+    }
+    // renders into the attached canvas, if any
+    pub(crate) fn redraw(&self) {
+        if self.rendering_suspended {
+            return;
+        }
+        if let Some(context) = &self.context {
+            context.render(self);
+            self.drawn_state.borrow_mut().copy_from(&self.state);
+        } else if let Some(webgl) = &self.webgl {
+            webgl.render(self);
+        }
+    }
+    // called when something other than `redraw` draws on the canvas
+    fn forget_drawn_state(&self) {
+        *self.drawn_state.borrow_mut() = BitGrid::default();
+    }
+    // a frame that is nothing but live cells in the fill style on the
+    // background, so a cell can be repainted on its own
+    fn draws_plain_cells(&self) -> bool {
+        self.fit_canvas.is_none()
+            && self.density_block <= 1
+            && !self.color_components
+            && self.hue_speed == 0.0
+            && self.motion_blur <= 0.0
+            && self.flash_color.is_none()
+            && self.decay.is_empty()
+            && self.seam_margin <= 0
+            && !self.show_ghost
+            && self.overlay.is_empty()
+    }
+    // Called after a committed generation: repaints only the cells that
+    // differ from the frame on the canvas. Falls back to `redraw` when the
+    // frame has more than plain cells to it, when the canvas doesn't show
+    // an earlier board of the same size, or when more cells changed than
+    // are alive, as drawing the board from scratch is then no more work.
+    pub(crate) fn redraw_changes(&self) {
+        if self.rendering_suspended {
+            return;
+        }
+        let context = match &self.context {
+            Some(context) => context,
+            None => return self.redraw(),
+        };
+        let mut drawn = self.drawn_state.borrow_mut();
+        let comparable = drawn.width() == self.state.width()
+            && drawn.height() == self.state.height()
+            && drawn.layout() == self.state.layout();
+        if !comparable || drawn.is_empty() || !self.draws_plain_cells() {
+            drop(drawn);
+            return self.redraw();
+        }
+        let changes: Vec<(usize, usize, bool)> = drawn.changed_cells(&self.state).collect();
+        if changes.len() as u32 > self.count_population() {
+            drop(drawn);
+            return self.redraw();
+        }
+        let cell = self.grid.cell as f64;
+        let fill_style = context.fill_style();
+        context.set_fill_style(&JsValue::from_str(&self.background_color));
+        for &(col, row, _) in changes.iter().filter(|&&(_, _, alive)| !alive) {
+            let (x, y) = (col as f64 * cell, row as f64 * cell);
+            context.clear_rect(x, y, cell, cell);
+            if !self.transparent_background {
+                context.fill_rect(x, y, cell, cell);
+            }
+        }
+        context.set_fill_style(&fill_style);
+        for &(col, row, _) in changes.iter().filter(|&&(_, _, alive)| alive) {
+            context.fill_rect(col as f64 * cell, row as f64 * cell, cell, cell);
+        }
+        drawn.copy_from(&self.state);
+    }
+    // wipes the whole canvas, with the 1px ring the border is stroked on
+    // and the seam margin, then paints it over unless the background is
+    // transparent; with motion blur the previous frame is only faded
+    fn clear(&self, context: &web_sys::CanvasRenderingContext2d) {
+        let margin = (self.seam_margin * self.grid.cell) as f64 + 1.0;
+        let (width, height) = self.canvas_css_size();
+        let origin = -margin.max(CANVAS_PADDING);
+        let (width, height) = (width - 2.0 * origin, height - 2.0 * origin);
+        if self.motion_blur > 0.0 {
+            context.save();
+            if self.transparent_background {
+                // erases in proportion to the alpha, whatever the color
+                let _ = context.set_global_composite_operation("destination-out");
+                context.set_fill_style(&JsValue::from_str("#000"));
+            } else {
+                context.set_fill_style(&JsValue::from_str(&self.background_color));
+            }
+            context.set_global_alpha(1.0 - self.motion_blur);
+            context.fill_rect(origin, origin, width, height);
+            context.restore();
+            return;
+        }
+        context.clear_rect(origin, origin, width, height);
+        if !self.transparent_background {
+            let fill_style = context.fill_style();
+            context.set_fill_style(&JsValue::from_str(&self.background_color));
+            context.fill_rect(origin, origin, width, height);
+            context.set_fill_style(&fill_style);
+        }
+    }
+    // copies of the cells within `seam_margin` of an edge, drawn past the
+    // opposite edge as if the board wrapped around
+    fn draw_seam_margin(&self, context: &web_sys::CanvasRenderingContext2d) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let margin = min(self.seam_margin, min(width, height));
+        if margin <= 0 {
+            return;
+        }
+        let cell = self.grid.cell as f64;
+        let in_margin = |position: i16, size: i16| position >= -margin && position < size + margin;
+        for (col_num, row_num) in self.state.live_cells() {
+            for &dx in &[-width, 0, width] {
+                for &dy in &[-height, 0, height] {
+                    let (x, y) = (col_num as i16 + dx, row_num as i16 + dy);
+                    if (dx, dy) != (0, 0) && in_margin(x, width) && in_margin(y, height) {
+                        context.fill_rect(x as f64 * cell, y as f64 * cell, cell, cell);
+                    }
+                }
+            }
+        }
+    }
+    fn draw_border(&self, context: &web_sys::CanvasRenderingContext2d) {
+        if !self.show_border {
+            return;
+        }
+        let width = (self.grid.cell * self.grid.width) as f64;
+        let height = (self.grid.cell * self.grid.height) as f64;
+        context.set_stroke_style(&JsValue::from_str(&self.border_color));
+        context.set_line_width(1.0);
+        // the half pixel offset keeps the 1px line just outside the cells
+        context.stroke_rect(-0.5, -0.5, width + 1.0, height + 1.0);
+    }
+    // Fills every (col, row) cell in `color` after a single style switch,
+    // restoring the gradient afterwards; callers drawing several colors
+    // bucket their cells by color first so each color costs one switch.
+    fn fill_cells(&self, context: &web_sys::CanvasRenderingContext2d, color: &str, cells: &[(usize, usize)]) {
+        if cells.is_empty() {
+            return;
+        }
+        let cell = self.grid.cell as f64;
+        let fill_style = context.fill_style();
+        context.set_fill_style(&JsValue::from_str(color));
+        for &(col_num, row_num) in cells {
+            context.fill_rect(col_num as f64 * cell, row_num as f64 * cell, cell, cell);
+        }
+        context.set_fill_style(&fill_style);
+    }
+    // dying cells of a Generations rule, fainter the closer they are to dead
+    fn draw_decay(&self, context: &web_sys::CanvasRenderingContext2d) {
+        if self.decay.is_empty() {
+            return;
+        }
+        let cell = self.grid.cell as f64;
+        let dying_states = self.rule.states.saturating_sub(2) as usize;
+        let mut by_steps = vec![vec![]; dying_states + 1];
+        for (col_num, col) in self.decay.iter().enumerate() {
+            for (row_num, &steps) in col.iter().enumerate() {
+                if steps > 0 && (steps as usize) < by_steps.len() {
+                    by_steps[steps as usize].push((col_num, row_num));
+                }
+            }
+        }
+        for (steps, cells) in by_steps.iter().enumerate().skip(1) {
+            context.set_global_alpha(steps as f64 / (dying_states + 1) as f64);
+            for &(col_num, row_num) in cells {
+                context.fill_rect(col_num as f64 * cell, row_num as f64 * cell, cell, cell);
+            }
+        }
+        context.set_global_alpha(1.0);
+    }
+    // one color per connected cluster, hues a golden angle apart; on a
+    // torus a cluster across the seam keeps one color
+    fn draw_components(&self, context: &web_sys::CanvasRenderingContext2d) {
+        let (labels, count) = self.label_components(self.wraps_edges());
+        let mut clusters = vec![vec![]; count as usize];
+        for (col_num, col) in labels.iter().enumerate() {
+            for (row_num, &label) in col.iter().enumerate() {
+                if label != 0 {
+                    clusters[label as usize - 1].push((col_num, row_num));
+                }
+            }
+        }
+        for (index, cells) in clusters.iter().enumerate() {
+            let hue = (index as f64 * 137.508) % 360.0;
+            self.fill_cells(context, &hsl_to_hex(hue, 0.75, 0.6), cells);
+        }
+    }
+    fn draw_walls(&self, context: &web_sys::CanvasRenderingContext2d) {
+        let mut walls = vec![];
+        for (col_num, col) in self.walls.iter().enumerate() {
+            for (row_num, &wall) in col.iter().enumerate() {
+                if wall {
+                    walls.push((col_num, row_num));
+                }
+            }
+        }
+        self.fill_cells(context, &self.wall_color, &walls);
+    }
+    // marks the cells the computed columns of the open generation change
+    fn draw_ghost(&self, context: &web_sys::CanvasRenderingContext2d) {
+        if !self.show_ghost || !self.generation_open {
+            return;
+        }
+        let (mut births, mut deaths) = (vec![], vec![]);
+        for (col_num, row_num, alive) in self.state.changed_cells(&self.interim_state) {
+            if col_num >= self.interim_columns {
+                break;
+            }
+            if alive { &mut births } else { &mut deaths }.push((col_num, row_num));
+        }
+        context.set_global_alpha(0.6);
+        self.fill_cells(context, GHOST_BIRTH_COLOR, &births);
+        self.fill_cells(context, GHOST_DEATH_COLOR, &deaths);
+        context.set_global_alpha(1.0);
+    }
+    // one square per block of cells, filled when any cell in it is alive
+    fn draw_fitted(&self, context: &web_sys::CanvasRenderingContext2d, width: f64, height: f64) {
+        let (cols, rows) = (self.grid.width, self.grid.height);
+        let (block, pixels) = self.fit_layout(width, height);
+        for col in (0..cols).step_by(block as usize) {
+            for row in (0..rows).step_by(block as usize) {
+                let density = self.tile_density(col, row, min(block, cols - col), min(block, rows - row));
+                if density > 0.0 {
+                    context.fill_rect(
+                        (col / block) as f64 * pixels,
+                        (row / block) as f64 * pixels,
+                        pixels,
+                        pixels);
+                }
+            }
+        }
+    }
+    // cells merged along each side of a block, and the CSS pixels each
+    // block takes, to fit the board into a `width` x `height` area
+    fn fit_layout(&self, width: f64, height: f64) -> (i16, f64) {
+        let (cols, rows) = (self.grid.width, self.grid.height);
+        let block = max(max((cols as f64 / width).ceil() as i16, (rows as f64 / height).ceil() as i16), 1);
+        let blocks = |cells: i16| ((cells + block - 1) / block) as f64;
+        (block, (width / blocks(cols)).min(height / blocks(rows)))
+    }
+    // CSS pixels per cell; when fitting merges blocks of cells into one
+    // pixel, each cell gets its share of the block
+    pub(crate) fn cell_pitch(&self) -> f64 {
+        match self.fit_canvas {
+            Some((width, height)) => {
+                let (block, pixels) = self.fit_layout(width, height);
+                pixels / block as f64
+            }
+            None => self.grid.cell as f64,
+        }
+    }
+    /// Cell under the CSS pixel `(px, py)`, measured from the top left
+    /// corner of the canvas element as mouse events' `offsetX` and `offsetY`
+    /// are, or `None` outside the board. Accounts for the padding, the cell
+    /// size and `fit_to_canvas`.
+    pub fn pixel_to_cell(&self, px: f64, py: f64) -> Option<(i16, i16)> {
+        let pitch = self.cell_pitch();
+        let x = ((px - CANVAS_PADDING) / pitch).floor();
+        let y = ((py - CANVAS_PADDING) / pitch).floor();
+        if x < 0.0 || x >= self.grid.width as f64 || y < 0.0 || y >= self.grid.height as f64 {
+            return None;
+        }
+        Some((x as i16, y as i16))
+    }
+    /// CSS pixel of the top left corner of cell `(x, y)`, in the
+    /// coordinates `pixel_to_cell` takes.
+    pub fn cell_to_pixel(&self, x: i16, y: i16) -> (f64, f64) {
+        let pitch = self.cell_pitch();
+        (CANVAS_PADDING + x as f64 * pitch, CANVAS_PADDING + y as f64 * pitch)
+    }
+    fn draw_density(&self, context: &web_sys::CanvasRenderingContext2d, block: i16) {
+        let cell = self.grid.cell as f64;
+        for col in (0..self.grid.width).step_by(block as usize) {
+            for row in (0..self.grid.height).step_by(block as usize) {
+                let width = min(block, self.grid.width - col);
+                let height = min(block, self.grid.height - row);
+                let density = self.tile_density(col, row, width, height);
+                if density == 0.0 {
+                    continue;
+                }
+                context.set_global_alpha(density);
+                context.fill_rect(
+                    col as f64 * cell,
+                    row as f64 * cell,
+                    width as f64 * cell,
+                    height as f64 * cell);
+            }
+        }
+        context.set_global_alpha(1.0);
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Game of `width` x `height` cells, each `cell_px` CSS pixels square,
+    /// drawing into the canvas element with id `canvas_id`, which is sized
+    /// to the board. It is filled as the init mode says, randomly to begin
+    /// with. Nothing runs by itself: the page drives it, typically calling
+    /// `tick` from `requestAnimationFrame`; a committed generation is drawn
+    /// right away.
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: &str, width: i16, height: i16, cell_px: i16) -> Result<Game, JsValue> {
+        Game::new_with_backend(canvas_id, width, height, cell_px, RenderBackend::Canvas2d)
+    }
+    /// `new` drawing with `backend`. A canvas can only ever have one kind
+    /// of context, so a canvas that was drawn on in 2d can't switch to
+    /// WebGL, and the other way round.
+    pub fn new_with_backend(canvas_id: &str, width: i16, height: i16, cell_px: i16, backend: RenderBackend)
+                            -> Result<Game, JsValue> {
+        let mut game = Game::with_canvas(canvas_id, width, height, cell_px, backend)?;
+        game.start();
+        Ok(game)
+    }
+    /// Draws the board into the game's canvas now, for a page that changes
+    /// the styling or wants a frame outside `tick`.
+    #[wasm_bindgen(js_name = render)]
+    pub fn render_now(&self) {
+        self.redraw();
+    }
+    /// Draws the board and its overlay into `context` with the current
+    /// styling, on top of whatever it holds: nothing is cleared, the board
+    /// lands at the context's current transform, and the context's state is
+    /// restored afterwards. The game's own canvas is untouched.
+    pub fn render_to(&self, context: &web_sys::CanvasRenderingContext2d) -> Result<(), JsValue> {
+        context.save();
+        let styled = self.apply_gradient(context);
+        if styled.is_ok() {
+            self.draw_board(context);
+            self.draw_overlay(context);
+        }
+        context.restore();
+        styled
+    }
+    /// Draws the board as a heatmap of `block` x `block` tiles, each filled
+    /// with an opacity matching its fraction of live cells.
+    pub fn render_density(&self, block: i16) {
+        if let Some(context) = &self.context {
+            self.clear(context);
+            self.draw_density(context, max(block, 1));
+            self.forget_drawn_state();
+        }
+    }
+    /// Draws the whole board into a `width` x `height` pixel area whatever
+    /// the cell size, merging blocks of cells into one pixel (alive when
+    /// any of them is) when the board has more cells than the area has
+    /// pixels. A zero or negative size goes back to the cell size.
+    pub fn fit_to_canvas(&mut self, width: f64, height: f64) {
+        self.fit_canvas = if width > 0.0 && height > 0.0 { Some((width, height)) } else { None };
+        self.redraw();
+    }
+    /// `pixel_to_cell` for JS, as `[x, y]`, or an empty array outside the
+    /// board.
+    #[wasm_bindgen(js_name = pixel_to_cell)]
+    pub fn pixel_to_cell_js(&self, px: f64, py: f64) -> Vec<i16> {
+        self.pixel_to_cell(px, py).map_or(vec![], |(x, y)| vec![x, y])
+    }
+    /// `cell_to_pixel` for JS, as `[px, py]`.
+    #[wasm_bindgen(js_name = cell_to_pixel)]
+    pub fn cell_to_pixel_js(&self, x: i16, y: i16) -> Vec<f64> {
+        let (px, py) = self.cell_to_pixel(x, y);
+        vec![px, py]
+    }
+    /// Rotates the gradient hues by `degrees` every generation for a slowly
+    /// shifting palette; 0 stops the cycle and restores the original colors.
+    pub fn set_hue_cycle(&mut self, degrees: f64) {
+        self.hue_speed = degrees;
+        if degrees == 0.0 {
+            self.hue_offset = 0.0;
+            if let Some(context) = &self.context {
+                let _ = self.apply_gradient(context);
+            }
+        }
+        self.redraw();
+    }
+    /// Draws a band of `margin` cells around the board holding the cells of
+    /// the opposite edges, so patterns crossing the seam of a wrapping board
+    /// look continuous. The canvas must have room for the band on every
+    /// side, beyond the usual 5px offset. 0 turns it off.
+    pub fn set_seam_margin(&mut self, margin: i16) {
+        self.seam_margin = max(margin, 0);
+        self.redraw();
+    }
+    /// Draws every cell `cell_px` CSS pixels wide (at least 1), resizing
+    /// the canvas to match.
+    pub fn set_cell_size(&mut self, cell_px: i16) -> Result<(), JsValue> {
+        self.grid.cell = max(cell_px, 1);
+        self.set_pixel_ratio(self.pixel_ratio)
+    }
+    /// Renders at `ratio` canvas pixels per CSS pixel, normally
+    /// `window.devicePixelRatio`, so the board stays sharp on high density
+    /// screens. The canvas attributes are sized to the board times `ratio`
+    /// and its CSS size is pinned to the board itself; without this (ratio
+    /// 1, the default) only the attributes are set and a CSS size that
+    /// differs from them stretches the board and blurs it.
+    pub fn set_pixel_ratio(&mut self, ratio: f64) -> Result<(), JsValue> {
+        self.pixel_ratio = if ratio > 0.0 { ratio } else { 1.0 };
+        if let Some(canvas) = &self.canvas {
+            let (css_width, css_height) = self.canvas_css_size();
+            canvas.style().set_property("width", &format!("{}px", css_width))?;
+            canvas.style().set_property("height", &format!("{}px", css_height))?;
+        }
+        self.size_canvas()?;
+        self.redraw();
+        Ok(())
+    }
+    /// Draws every connected cluster of cells in its own color, so separate
+    /// objects stand out. Clusters are labelled anew every frame, a flood
+    /// fill over the whole board plus a style switch per cluster, which is
+    /// noticeably slower than plain rendering on busy boards. Labels follow
+    /// scan order, so a cluster keeps its color only while the clusters
+    /// before it stay put.
+    pub fn set_color_components(&mut self, enabled: bool) {
+        self.color_components = enabled;
+        self.redraw();
+    }
+    /// Draws the cells born in the last generation in `color` for the frame
+    /// that shows that generation, after which they look like any other
+    /// live cell. `undefined` turns the flash off.
+    pub fn set_birth_flash(&mut self, color: Option<String>) {
+        self.flash_color = color;
+        self.last_births = vec![];
+        self.redraw();
+    }
+    /// Overlays the changes the open generation makes: green where a cell
+    /// will be born, red where one will die. Pair with
+    /// `preview_generation`; a sequential update writes cells to the board
+    /// as it goes, so it shows no difference.
+    pub fn set_show_ghost(&mut self, show: bool) {
+        self.show_ghost = show;
+        self.redraw();
+    }
+    /// Runs `count` generations, rendering each and collecting the canvas
+    /// as a PNG data URL, for assembling GIFs or videos. Everything happens
+    /// in this one call, so the page is unresponsive until it returns; on
+    /// big boards capture in small batches between animation frames.
+    pub fn capture_frames(&mut self, count: u32) -> Result<js_sys::Array, JsValue> {
+        let canvas = self.canvas.clone().ok_or("a headless game has no canvas to capture")?;
+        let frames = js_sys::Array::new();
+        for _ in 0..count {
+            self.begin_generation();
+            self.commit_generation();
+            frames.push(&JsValue::from(canvas.to_data_url()?));
+        }
+        Ok(frames)
+    }
+    /// Like `capture_frames`, but returns the board area of each rendered
+    /// frame as raw RGBA pixels, concatenated, skipping the PNG encoding.
+    /// A frame is `frame_width` x `frame_height` pixels: the board's CSS
+    /// size times the pixel ratio, so `set_pixel_ratio` scales it.
+    /// `frame_count` and `frame_stride` give the layout for slicing.
+    pub fn capture_frames_rgba(&mut self, count: u32) -> Result<Vec<u8>, JsValue> {
+        let context = self.context.clone().ok_or("only a game drawing on a 2d canvas can capture raw frames")?;
+        let origin = (CANVAS_PADDING * self.pixel_ratio).round();
+        let pixels = |cells: i16| (cells as f64 * self.cell_pitch() * self.pixel_ratio).round();
+        let (width, height) = (pixels(self.grid.width), pixels(self.grid.height));
+        let mut frames = Vec::with_capacity(count as usize * width as usize * height as usize * 4);
+        self.frame_count = 0;
+        self.frame_width = width as u32;
+        self.frame_height = height as u32;
+        for _ in 0..count {
+            self.begin_generation();
+            self.commit_generation();
+            let image = context.get_image_data(origin, origin, width, height)?;
+            frames.extend_from_slice(&image.data());
+            self.frame_count += 1;
+        }
+        Ok(frames)
+    }
+    /// Frames returned by the last `capture_frames_rgba`.
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+    /// Width, in pixels, of each frame of the last `capture_frames_rgba`.
+    pub fn frame_width(&self) -> u32 {
+        self.frame_width
+    }
+    /// Height, in pixels, of each frame of the last `capture_frames_rgba`.
+    pub fn frame_height(&self) -> u32 {
+        self.frame_height
+    }
+    /// Bytes per frame of the last `capture_frames_rgba`; frame `i` starts
+    /// at `i * frame_stride`.
+    pub fn frame_stride(&self) -> u32 {
+        self.frame_width * self.frame_height * 4
+    }
+    /// Tears the game down: stops the page loop driving it (at its next
+    /// frame or timer), removes the page listeners registered for it, drops
+    /// the JS callbacks it holds and clears and detaches the canvas. The
+    /// game must not be used afterwards; free it from JS once done.
+    pub fn destroy(&mut self) {
+        for listener in self.listeners.drain(..) {
+            let callback = listener.closure.as_ref().unchecked_ref();
+            let _ = listener.target.remove_event_listener_with_callback(listener.event, callback);
+        }
+        if let Some(context) = &self.context {
+            self.clear(context);
+        }
+        if let Some(webgl) = &self.webgl {
+            webgl.clear();
+        }
+        self.population_events.callback = None;
+        self.breakpoint = Breakpoint::default();
+        self.log_sink = None;
+        self.context = None;
+        self.webgl = None;
+        self.canvas = None;
+        self.performance = None;
+        self.destroyed = true;
+    }
+    /// Outlines the simulated region, where the dead boundary lies.
+    pub fn set_show_border(&mut self, show: bool) {
+        self.show_border = show;
+        self.redraw();
+    }
+    /// CSS color of the region outline.
+    pub fn set_border_color(&mut self, color: &str) {
+        self.border_color = color.to_string();
+        self.redraw();
+    }
+    /// Whether clearing leaves the board transparent, showing the page
+    /// behind it (the default), or paints it with the background color.
+    pub fn set_transparent_background(&mut self, transparent: bool) {
+        self.transparent_background = transparent;
+        self.redraw();
+    }
+    /// CSS color an opaque background is painted with, white by default.
+    pub fn set_background_color(&mut self, color: &str) {
+        self.background_color = color.to_string();
+        self.redraw();
+    }
+    /// How much of the previous frame bleeds into the next, from 0 (none,
+    /// the default) to 0.99: instead of being cleared, the last frame is
+    /// faded by `1 - amount`, so moving cells leave soft trails. Only
+    /// rendering changes.
+    pub fn set_motion_blur(&mut self, amount: f64) {
+        self.motion_blur = amount.clamp(0.0, 0.99);
+    }
+    /// Makes every frame a density heatmap of `block` x `block` tiles;
+    /// a block of 1 goes back to drawing each cell.
+    pub fn set_density_block(&mut self, block: i16) {
+        self.density_block = max(block, 1);
+    }
+}
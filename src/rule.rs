@@ -0,0 +1,389 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::isotropic::IsotropicTable;
+
+/// Life-like rule over a square neighbourhood: a cell counts the live cells
+/// in the square of side `2 * radius + 1` around it (itself excluded) and
+/// survives or is born when that count falls within one of the matching
+/// ranges. Radius 1 gives the B/S rules, larger radii Larger than Life.
+/// With more than 2 `states` it is a Generations rule, where cells that
+/// die go through `states - 2` dying states first.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub(crate) radius: i16,
+    pub(crate) survival: Vec<RangeInclusive<u32>>,
+    pub(crate) birth: Vec<RangeInclusive<u32>>,
+    pub(crate) states: u8,
+    // per configuration outcomes of an isotropic non-totalistic rule,
+    // which then take precedence over the ranges
+    pub(crate) isotropic: Option<IsotropicTable>,
+}
+
+impl Default for Rule {
+    /// Classic Life, B3/S23 on the radius 1 Moore neighbourhood.
+    fn default() -> Rule {
+        Rule {
+            radius: 1,
+            survival: vec![2..=3],
+            birth: vec![3..=3],
+            states: 2,
+            isotropic: None,
+        }
+    }
+}
+
+// digits 0-8, each a neighbour count, as contiguous ranges
+pub(crate) fn digits_to_ranges(digits: &str) -> Result<Vec<RangeInclusive<u32>>, String> {
+    let mut counts = [false; 9];
+    for digit in digits.chars() {
+        match digit.to_digit(10) {
+            Some(count) if count < 9 => counts[count as usize] = true,
+            _ => return Err(format!("invalid neighbour count {:?} in rule", digit)),
+        }
+    }
+    let mut ranges = vec![];
+    let mut first = None;
+    for count in 0..=9 {
+        match (count < 9 && counts[count as usize], first) {
+            (true, None) => first = Some(count),
+            (false, Some(start)) => {
+                ranges.push(start..=count - 1);
+                first = None;
+            }
+            _ => {}
+        }
+    }
+    Ok(ranges)
+}
+
+/// Largest radius a rule can have, which keeps it to a byte in snapshots.
+pub(crate) const MAX_RADIUS: i16 = 255;
+
+// spans of neighbour counts written `start<separator>end` or as a single
+// count, separated by commas, each within 0..=`max`
+fn parse_spans(spans: &str, separator: &str, max: u32) -> Result<Vec<RangeInclusive<u32>>, String> {
+    let count = |count: &str| count.trim().parse::<u32>().map_err(|_| format!("invalid neighbour count {:?} in rule", count));
+    let mut ranges = vec![];
+    for span in spans.split(',').filter(|span| !span.trim().is_empty()) {
+        let mut bounds = span.splitn(2, separator);
+        let start = count(bounds.next().unwrap_or(""))?;
+        let end = bounds.next().map_or(Ok(start), count)?;
+        if start > end || end > max {
+            return Err(format!("invalid neighbour count range {:?} in rule, counts go from 0 to {}", span, max));
+        }
+        ranges.push(start..=end);
+    }
+    Ok(ranges)
+}
+
+impl Rule {
+    /// Parses a rulestring in any of the usual forms: B/S notation such as
+    /// `B36/S23` (Hensel letters allowed, see `parse_isotropic`),
+    /// optionally followed by `/Cn` for an `n` state Generations rule,
+    /// survival/birth digits such as `23/36`, the `survival/birth/states`
+    /// form of `parse_generations`, or a Larger than Life rule as
+    /// `parse_larger_than_life` reads it. Whatever `Display` writes parses
+    /// back to the same rule.
+    pub fn parse(rule: &str) -> Result<Rule, String> {
+        let notation = rule.trim();
+        let mut chars = notation.chars();
+        if chars.next().map(|first| first.to_ascii_uppercase()) == Some('R') && chars.next().is_some_and(|second| second.is_ascii_digit()) {
+            return Rule::parse_larger_than_life(notation);
+        }
+        let parts: Vec<&str> = notation.split('/').collect();
+        let letter = |part: &str, letter: char| part.chars().next().map(|first| first.to_ascii_uppercase()) == Some(letter);
+        let is_bs = parts.len() >= 2 && letter(parts[0], 'B') && letter(parts[1], 'S');
+        match (is_bs, parts.len()) {
+            (true, 2) => Rule::parse_isotropic(notation),
+            (true, 3) => {
+                let states = parts[2]
+                    .trim_start_matches(&['C', 'c'][..])
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&states| states >= 2)
+                    .ok_or_else(|| format!("invalid state count {:?} in rule", parts[2]))?;
+                let rule = Rule::parse_isotropic(&parts[..2].join("/"))?;
+                if rule.isotropic.is_some() && states > 2 {
+                    return Err(format!("Hensel letters are not supported in Generations rules, got {:?}", notation));
+                }
+                Ok(Rule { states, ..rule })
+            }
+            (false, 2) => Ok(Rule {
+                survival: digits_to_ranges(parts[0])?,
+                birth: digits_to_ranges(parts[1])?,
+                ..Rule::default()
+            }),
+            (false, 3) => Rule::parse_generations(notation),
+            _ => Err(format!("expected a rulestring such as B3/S23 or 23/3, got {:?}", rule)),
+        }
+    }
+    /// Parses a Generations rule in `survival/birth/states` form, e.g.
+    /// `345/2/4`. `states` is from 2, plain Life-like, to 255.
+    pub fn parse_generations(rule: &str) -> Result<Rule, String> {
+        let parts: Vec<&str> = rule.trim().split('/').collect();
+        if parts.len() != 3 {
+            return Err(format!("expected survival/birth/states, got {:?}", rule));
+        }
+        let states = parts[2]
+            .parse::<u8>()
+            .ok()
+            .filter(|&states| states >= 2)
+            .ok_or_else(|| format!("invalid state count {:?} in rule", parts[2]))?;
+        Ok(Rule {
+            radius: 1,
+            survival: digits_to_ranges(parts[0])?,
+            birth: digits_to_ranges(parts[1])?,
+            states,
+            isotropic: None,
+        })
+    }
+    /// Parses a Larger than Life rule of radius 1 to 255, either as
+    /// `Display` writes it, `R5,B34-45/S34-58` (each side a comma separated
+    /// list of `start-end` spans or single counts, optionally followed by
+    /// `/Cn` for `n` states), or in Golly's `R5,C0,M0,S34..58,B34..45,NM`
+    /// form. Golly's `M1`, where a cell counts itself, is turned into
+    /// survival counts without it; only the Moore neighbourhood (`NM`) is
+    /// supported.
+    pub fn parse_larger_than_life(rule: &str) -> Result<Rule, String> {
+        let notation = rule.trim();
+        let invalid = || format!("expected a rule such as R5,B34-45/S34-58 or R5,C0,M0,S34..58,B34..45,NM, got {:?}", rule);
+        let (radius, rest) = notation[1..].split_at(notation[1..].find(',').ok_or_else(invalid)?);
+        let radius = radius
+            .parse::<i16>()
+            .ok()
+            .filter(|radius| (1..=MAX_RADIUS).contains(radius))
+            .ok_or_else(|| format!("invalid radius {:?} in rule, expected 1 to {}", radius, MAX_RADIUS))?;
+        let rest = &rest[1..];
+        let max = {
+            let side = (2 * radius + 1) as u32;
+            side * side - 1
+        };
+        let mut rule = Rule { radius, survival: vec![], birth: vec![], ..Rule::default() };
+        let letter = |part: &str| part.chars().next().map(|first| first.to_ascii_uppercase());
+        if letter(rest) == Some('B') && rest.contains('/') {
+            let parts: Vec<&str> = rest.split('/').collect();
+            if parts.len() > 3 || letter(parts[1]) != Some('S') {
+                return Err(invalid());
+            }
+            rule.birth = parse_spans(&parts[0][1..], "-", max)?;
+            rule.survival = parse_spans(&parts[1][1..], "-", max)?;
+            if let Some(states) = parts.get(2) {
+                rule.states = states
+                    .trim_start_matches(&['C', 'c'][..])
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&states| states >= 2)
+                    .ok_or_else(|| format!("invalid state count {:?} in rule", states))?;
+            }
+            return Ok(rule);
+        }
+        let mut counts_itself = false;
+        for field in rest.split(',').map(str::trim) {
+            let value = field.get(1..).unwrap_or("");
+            match letter(field) {
+                Some('C') => {
+                    let states = value.parse::<u8>().map_err(|_| format!("invalid state count {:?} in rule", field))?;
+                    rule.states = states.max(2);
+                }
+                Some('M') if value == "0" || value == "1" => counts_itself = value == "1",
+                Some('S') => rule.survival = parse_spans(value, "..", max + 1)?,
+                Some('B') => rule.birth = parse_spans(value, "..", max)?,
+                Some('N') if value.eq_ignore_ascii_case("M") => {}
+                Some('N') => return Err(format!("only the Moore neighbourhood (NM) is supported, got {:?}", field)),
+                _ => return Err(invalid()),
+            }
+        }
+        if counts_itself {
+            // a live cell saw itself among its neighbours
+            rule.survival = rule
+                .survival
+                .iter()
+                .filter(|range| *range.end() > 0)
+                .map(|range| range.start().saturating_sub(1)..=range.end() - 1)
+                .collect();
+        } else if rule.survival.iter().any(|range| *range.end() > max) {
+            return Err(format!("invalid survival count in rule {:?}, counts go from 0 to {}", notation, max));
+        }
+        Ok(rule)
+    }
+    // number of distinct neighbour counts, 0 up to every neighbour alive
+    pub(crate) fn count_len(&self) -> u32 {
+        let side = (2 * self.radius + 1) as u32;
+        side * side
+    }
+    pub(crate) fn survives(&self, nebour_count: u32) -> bool {
+        self.survival.iter().any(|range| range.contains(&nebour_count))
+    }
+    pub(crate) fn is_born(&self, nebour_count: u32) -> bool {
+        self.birth.iter().any(|range| range.contains(&nebour_count))
+    }
+}
+
+// writes each count the ranges cover, as digits for radius 1 rules and
+// as comma separated `start-end` spans for larger ones
+fn write_counts(f: &mut fmt::Formatter, radius: i16, ranges: &[RangeInclusive<u32>]) -> fmt::Result {
+    for (index, range) in ranges.iter().enumerate() {
+        if radius == 1 {
+            for count in range.clone() {
+                write!(f, "{}", count)?;
+            }
+        } else {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}-{}", range.start(), range.end())?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Rule {
+    /// B/S notation, `B3/S23` for Life, with `/Cn` at the end of a
+    /// Generations rule of `n` states. A larger radius `r` with one birth
+    /// and one survival range is written the way Golly writes Larger than
+    /// Life, `Rr,C0,M0,Sa..b,Bc..d,NM`; with more ranges it is prefixed
+    /// with `Rr,` and lists them as `start-end` spans. Isotropic rules are
+    /// written as they were parsed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(table) = &self.isotropic {
+            return write!(f, "{}", table.notation);
+        }
+        if let (true, [survival], [birth]) = (self.radius != 1, &self.survival[..], &self.birth[..]) {
+            let states = if self.states > 2 { self.states } else { 0 };
+            return write!(f, "R{},C{},M0,S{}..{},B{}..{},NM", self.radius, states,
+                          survival.start(), survival.end(), birth.start(), birth.end());
+        }
+        if self.radius != 1 {
+            write!(f, "R{},", self.radius)?;
+        }
+        write!(f, "B")?;
+        write_counts(f, self.radius, &self.birth)?;
+        write!(f, "/S")?;
+        write_counts(f, self.radius, &self.survival)?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+/// Well known radius 1 rules by their B/S notation.
+const NAMED_RULES: &[(&str, &str)] = &[
+    ("B3/S23", "Conway's Life"),
+    ("B36/S23", "HighLife"),
+    ("B2/S", "Seeds"),
+    ("B3678/S34678", "Day & Night"),
+    ("B3/S012345678", "Life without Death"),
+    ("B1357/S1357", "Replicator"),
+    ("B3/S12345", "Maze"),
+    ("B3/S1234", "Mazectric"),
+    ("B36/S125", "2x2"),
+    ("B368/S245", "Morley"),
+    ("B4678/S35678", "Anneal"),
+    ("B35678/S5678", "Diamoeba"),
+    ("B3/S45678", "Coral"),
+    ("B345/S5", "Long Life"),
+    ("B1/S1", "Gnarl"),
+    ("B34/S34", "34 Life"),
+    ("B357/S1358", "Amoeba"),
+    ("B345/S4567", "Assimilation"),
+    ("B5678/S45678", "Vote"),
+    ("B45678/S2345", "Walled Cities"),
+    ("B3678/S235678", "Stains"),
+    ("B234/S", "Serviettes"),
+    ("B37/S23", "DryLife"),
+    ("B38/S23", "Pedestrian Life"),
+    ("B3/S12", "Flock"),
+    ("B2/S0", "Live Free or Die"),
+    ("B357/S238", "Pseudo Life"),
+    ("B2/S/C3", "Brian's Brain"),
+    ("B2/S345/C4", "Star Wars"),
+    ("B2/S/C4", "Frogs"),
+    ("B3/S23/C3", "Life (3 states)"),
+];
+
+/// Friendly name of a well known rule, such as "Conway's Life" or
+/// "HighLife", or its B/S notation when it has none.
+pub fn rule_name(rule: &Rule) -> String {
+    let notation = rule.to_string();
+    NAMED_RULES
+        .iter()
+        .find(|(known, _)| *known == notation)
+        .map_or(notation.clone(), |(_, name)| name.to_string())
+}
+
+pub(crate) fn ranges_to_array(ranges: &[RangeInclusive<u32>], len: u32) -> js_sys::Array {
+    let array = js_sys::Array::new();
+    for count in 0..len {
+        array.push(&JsValue::from_bool(ranges.iter().any(|range| range.contains(&count))));
+    }
+    array
+}
+
+// B0..B8 / S0..S8 checkboxes, one truthy entry per neighbour count
+pub(crate) fn array_to_ranges(rule: &JsValue, key: &str) -> Result<Vec<RangeInclusive<u32>>, JsValue> {
+    let value = js_sys::Reflect::get(rule, &JsValue::from_str(key))?;
+    let array = value
+        .dyn_ref::<js_sys::Array>()
+        .ok_or_else(|| format!("rule `{}` must be an array", key))?;
+    if array.length() != 9 {
+        return Err(format!("rule `{}` must have 9 entries, got {}", key, array.length()).into());
+    }
+    let mut ranges = vec![];
+    let mut first = None;
+    for count in 0..=9 {
+        let set = count < 9 && js_sys::Reflect::get(array, &JsValue::from(count))?.is_truthy();
+        match (set, first) {
+            (true, None) => first = Some(count),
+            (false, Some(start)) => {
+                ranges.push(start..=count - 1);
+                first = None;
+            }
+            _ => {}
+        }
+    }
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ltl(radius: i16, birth: Vec<RangeInclusive<u32>>, survival: Vec<RangeInclusive<u32>>, states: u8) -> Rule {
+        Rule { radius, birth, survival, states, isotropic: None }
+    }
+
+    fn same(a: &Rule, b: &Rule) -> bool {
+        (a.radius, &a.birth, &a.survival, a.states) == (b.radius, &b.birth, &b.survival, b.states)
+    }
+
+    #[test]
+    fn larger_than_life_rules_round_trip() {
+        for rule in &[
+            ltl(5, vec![34..=45], vec![34..=58], 2),
+            ltl(2, vec![1..=2, 7..=9], vec![0..=0, 4..=6], 2),
+            ltl(3, vec![5..=5], vec![], 4),
+            ltl(7, vec![10..=20], vec![10..=30], 3),
+        ] {
+            let parsed = Rule::parse(&rule.to_string()).unwrap();
+            assert!(same(&parsed, rule), "{} parsed as {:?}", rule, parsed);
+        }
+        assert_eq!(ltl(5, vec![34..=45], vec![34..=58], 2).to_string(), "R5,C0,M0,S34..58,B34..45,NM");
+        assert_eq!(ltl(2, vec![1..=2, 7..=9], vec![4..=6], 2).to_string(), "R2,B1-2,7-9/S4-6");
+    }
+
+    #[test]
+    fn golly_larger_than_life_rules_parse() {
+        let bosco = Rule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+        assert!(same(&bosco, &ltl(5, vec![34..=45], vec![33..=57], 2)));
+        let generations = Rule::parse("R2,C3,M0,S2..4,B3..3,NM").unwrap();
+        assert!(same(&generations, &ltl(2, vec![3..=3], vec![2..=4], 3)));
+        assert!(Rule::parse("R2,C0,M0,S2..4,B3..3,NN").is_err());
+        assert!(Rule::parse("R0,B1/S1").is_err());
+        assert!(Rule::parse("R1,B3-2/S2-3").is_err());
+        assert!(Rule::parse("R1,B3/S2-9").is_err());
+    }
+}
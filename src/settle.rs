@@ -0,0 +1,85 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::{BitOrder, Game};
+use crate::period::PeriodDetector;
+
+/// Longest cycle `evolve_until_settled` looks for when period detection is off.
+const SETTLE_PERIOD: u32 = 64;
+
+/// Fate of a board after `Game::evolve_until_settled`. Generations are
+/// counted from the start of the call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Settlement {
+    /// Every cell was dead after `generation` generations.
+    Extinct { generation: u32 },
+    /// From `generation` on the board no longer changes.
+    Stable { generation: u32 },
+    /// From `generation` on the board repeats every `period` generations.
+    Oscillating { generation: u32, period: u32 },
+    /// None of the above within the generations allowed.
+    StillRunning,
+}
+
+impl Settlement {
+    // `{ fate, generation, period }` as `evolve_until_settled` reports it
+    pub(crate) fn to_js(self) -> Result<js_sys::Object, JsValue> {
+        let (fate, generation, period) = match self {
+            Settlement::Extinct { generation } => ("extinct", Some(generation), None),
+            Settlement::Stable { generation } => ("stable", Some(generation), None),
+            Settlement::Oscillating { generation, period } => ("oscillating", Some(generation), Some(period)),
+            Settlement::StillRunning => ("running", None, None),
+        };
+        let result = js_sys::Object::new();
+        js_sys::Reflect::set(&result, &"fate".into(), &JsValue::from_str(fate))?;
+        if let Some(generation) = generation {
+            js_sys::Reflect::set(&result, &"generation".into(), &JsValue::from(generation))?;
+        }
+        if let Some(period) = period {
+            js_sys::Reflect::set(&result, &"period".into(), &JsValue::from(period))?;
+        }
+        Ok(result)
+    }
+}
+
+impl Game {
+    /// Runs up to `max` generations without rendering, stopping as soon as
+    /// the board dies out or starts repeating. Cycles are looked for within
+    /// the `set_max_period` window, or 64 generations when that is off.
+    pub fn evolve_until_settled(&mut self, max: u32) -> Settlement {
+        let suspended = std::mem::replace(&mut self.rendering_suspended, true);
+        let mut detector = PeriodDetector::new(self.period_detector.max_period().max(SETTLE_PERIOD));
+        detector.observe(self.pack_cells(BitOrder::LsbFirst));
+        let mut ran = 0;
+        let settlement = loop {
+            if self.count_population() == 0 {
+                break Settlement::Extinct { generation: ran };
+            }
+            match detector.period() {
+                Some(1) => break Settlement::Stable { generation: ran - 1 },
+                Some(period) => break Settlement::Oscillating { generation: ran - period, period },
+                None => {}
+            }
+            if ran == max {
+                break Settlement::StillRunning;
+            }
+            self.begin_generation();
+            self.commit_generation();
+            detector.observe(self.pack_cells(BitOrder::LsbFirst));
+            ran += 1;
+        };
+        self.rendering_suspended = suspended;
+        self.redraw();
+        settlement
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// `evolve_until_settled` for JS, as `{ fate, generation, period }`
+    /// where `fate` is `"extinct"`, `"stable"`, `"oscillating"` or
+    /// `"running"`; `generation` and `period` are only set when they apply.
+    #[wasm_bindgen(js_name = evolve_until_settled)]
+    pub fn evolve_until_settled_js(&mut self, max: u32) -> Result<JsValue, JsValue> {
+        Ok(self.evolve_until_settled(max).to_js()?.into())
+    }
+}
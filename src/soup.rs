@@ -0,0 +1,57 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+use crate::settle::Settlement;
+
+/// One trial of `Game::soup_search`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoupResult {
+    /// Seed the soup was drawn from; `set_seed` with it and a random
+    /// `restart` without warmup on a board like this one replays the trial.
+    pub seed: u64,
+    /// Generations run: the limit for a soup still running, otherwise
+    /// however long it took to see that it settled.
+    pub generations: u32,
+    pub settlement: Settlement,
+}
+
+impl Game {
+    /// Runs `trials` random soups on headless copies of this board's size,
+    /// rules and walls, each until it settles or `max_gens` generations
+    /// pass. Trial `i` is seeded with `i`, so results repeat from call to
+    /// call. The game itself is untouched.
+    pub fn soup_search(&self, trials: u32, max_gens: u32) -> Vec<SoupResult> {
+        (0..trials)
+            .map(|trial| {
+                let seed = trial as u64;
+                let mut soup = self.headless_copy();
+                soup.set_seed(seed);
+                soup.state = soup.generate_initial_state();
+                soup.board_replaced();
+                let settlement = soup.evolve_until_settled(max_gens);
+                SoupResult {
+                    seed,
+                    generations: soup.generation as u32,
+                    settlement,
+                }
+            })
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// `soup_search` for JS, as an array of `{ seed, generations, fate,
+    /// generation, period }` with the fields of `evolve_until_settled`.
+    #[wasm_bindgen(js_name = soup_search)]
+    pub fn soup_search_js(&self, trials: u32, max_gens: u32) -> Result<js_sys::Array, JsValue> {
+        let results = js_sys::Array::new();
+        for result in self.soup_search(trials, max_gens) {
+            let entry = result.settlement.to_js()?;
+            js_sys::Reflect::set(&entry, &"seed".into(), &JsValue::from(result.seed as f64))?;
+            js_sys::Reflect::set(&entry, &"generations".into(), &JsValue::from(result.generations))?;
+            results.push(&entry);
+        }
+        Ok(results)
+    }
+}
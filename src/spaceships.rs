@@ -0,0 +1,222 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+use crate::pattern::Pattern;
+
+/// Generations a sighting is remembered for, long enough to bridge a ship
+/// straddling the seam, where it isn't matched.
+const SIGHTING_TTL: u64 = 16;
+
+/// The spaceships looked for, all of period 4 under Conway's rule.
+const SHIPS: [&str; 2] = ["glider", "lwss"];
+
+/// One phase of a ship in one orientation.
+#[derive(Debug)]
+struct ShipShape {
+    kind: usize,
+    // `mask[x][y]` over the bounding box
+    mask: Vec<Vec<bool>>,
+    // first live cell in column-major order
+    anchor: (i16, i16),
+    // displacement over one period
+    velocity: (i16, i16),
+}
+
+#[derive(Debug)]
+struct Sighting {
+    kind: usize,
+    velocity: (i16, i16),
+    x: i16,
+    y: i16,
+    generation: u64,
+}
+
+/// Opt-in matching of spaceships on the board, following them from one
+/// generation to the next to count those coming back on the opposite side
+/// of the seam.
+#[derive(Debug, Default)]
+pub(crate) struct SpaceshipTracker {
+    // empty while tracking is off
+    shapes: Vec<ShipShape>,
+    sightings: Vec<Sighting>,
+    ships: u32,
+    crossings: u32,
+}
+
+impl SpaceshipTracker {
+    pub(crate) fn enabled(&self) -> bool {
+        !self.shapes.is_empty()
+    }
+}
+
+// one Conway generation over a handful of cells
+fn life_step(cells: &[(i16, i16)]) -> Vec<(i16, i16)> {
+    let left = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let right = cells.iter().map(|&(x, _)| x).max().unwrap_or(0);
+    let top = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let bottom = cells.iter().map(|&(_, y)| y).max().unwrap_or(0);
+    let mut next = vec![];
+    for x in left - 1..=right + 1 {
+        for y in top - 1..=bottom + 1 {
+            let mut count = 0;
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if (dx, dy) != (0, 0) && cells.contains(&(x + dx, y + dy)) {
+                        count += 1;
+                    }
+                }
+            }
+            if count == 3 || (count == 2 && cells.contains(&(x, y))) {
+                next.push((x, y));
+            }
+        }
+    }
+    next
+}
+
+fn corner(cells: &[(i16, i16)]) -> (i16, i16) {
+    (
+        cells.iter().map(|&(x, _)| x).min().unwrap_or(0),
+        cells.iter().map(|&(_, y)| y).min().unwrap_or(0),
+    )
+}
+
+// every phase of every ship in all eight orientations, without repeats
+fn ship_shapes() -> Vec<ShipShape> {
+    let mut shapes: Vec<(Pattern, ShipShape)> = vec![];
+    for (kind, name) in SHIPS.iter().enumerate() {
+        let mut phase = Pattern::named(name).map(|pattern| pattern.cells).unwrap_or_default();
+        for _ in 0..4 {
+            for &(swap, flip_x, flip_y) in &[
+                (false, 1, 1), (false, -1, 1), (false, 1, -1), (false, -1, -1),
+                (true, 1, 1), (true, -1, 1), (true, 1, -1), (true, -1, -1),
+            ] {
+                let oriented: Vec<(i16, i16)> = phase
+                    .iter()
+                    .map(|&(x, y)| if swap { (y, x) } else { (x, y) })
+                    .map(|(x, y)| (x * flip_x, y * flip_y))
+                    .collect();
+                let mut pattern = Pattern::new(oriented.clone());
+                pattern.cells.sort();
+                if shapes.iter().any(|(seen, _)| *seen == pattern) {
+                    continue;
+                }
+                let moved = (0..4).fold(oriented.clone(), |cells, _| life_step(&cells));
+                let (from, to) = (corner(&oriented), corner(&moved));
+                let mut mask = vec![vec![false; pattern.height as usize]; pattern.width as usize];
+                for &(x, y) in &pattern.cells {
+                    mask[x as usize][y as usize] = true;
+                }
+                let shape = ShipShape {
+                    kind,
+                    mask,
+                    anchor: pattern.cells[0],
+                    velocity: (to.0 - from.0, to.1 - from.1),
+                };
+                shapes.push((pattern, shape));
+            }
+            phase = life_step(&phase);
+        }
+    }
+    shapes.into_iter().map(|(_, shape)| shape).collect()
+}
+
+impl Game {
+    // the shape with its bounding box at (left, top), ringed by dead cells
+    fn matches_ship(&self, shape: &ShipShape, left: i16, top: i16) -> bool {
+        let (width, height) = (shape.mask.len() as i16, shape.mask[0].len() as i16);
+        for x in -1..=width {
+            for y in -1..=height {
+                let (col, row) = (left + x, top + y);
+                let alive = self.grid.contains(col, row)
+                    && self.state.get(col as usize, row as usize);
+                let expected = x >= 0 && x < width && y >= 0 && y < height
+                    && shape.mask[x as usize][y as usize];
+                if alive != expected {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+    // called once per committed generation
+    pub(crate) fn track_spaceships(&mut self) {
+        if !self.spaceships.enabled() {
+            return;
+        }
+        let mut found = vec![];
+        for (col, row) in self.state.live_cells() {
+            for shape in &self.spaceships.shapes {
+                let (left, top) = (col as i16 - shape.anchor.0, row as i16 - shape.anchor.1);
+                if self.matches_ship(shape, left, top) {
+                    found.push(Sighting {
+                        kind: shape.kind,
+                        velocity: shape.velocity,
+                        x: left,
+                        y: top,
+                        generation: self.generation,
+                    });
+                }
+            }
+        }
+
+        // the shortest way round from `from` to `to` along an axis of
+        // `size` cells, and whether it wraps
+        let delta = |from: i16, to: i16, size: i16| {
+            let direct = to - from;
+            if direct > size / 2 {
+                (direct - size, true)
+            } else if direct < -size / 2 {
+                (direct + size, true)
+            } else {
+                (direct, false)
+            }
+        };
+        let (width, height) = (self.grid.width, self.grid.height);
+        let tracker = &mut self.spaceships;
+        for sighting in &found {
+            let previous = tracker.sightings.iter().position(|seen| {
+                let reach = (sighting.generation - seen.generation).div_ceil(2) as i16 + 3;
+                seen.kind == sighting.kind
+                    && seen.velocity == sighting.velocity
+                    && delta(seen.x, sighting.x, width).0.abs() <= reach
+                    && delta(seen.y, sighting.y, height).0.abs() <= reach
+            });
+            if let Some(index) = previous {
+                let seen = tracker.sightings.swap_remove(index);
+                if delta(seen.x, sighting.x, width).1 || delta(seen.y, sighting.y, height).1 {
+                    tracker.crossings += 1;
+                }
+            }
+        }
+        let generation = self.generation;
+        tracker.sightings.retain(|seen| generation - seen.generation < SIGHTING_TTL);
+        tracker.ships = found.len() as u32;
+        tracker.sightings.extend(found);
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Looks for gliders and lightweight spaceships, in any phase and
+    /// direction and cut off from other cells, after every generation, and
+    /// counts the ones that cross the seam: seen near one edge, then near
+    /// the opposite one heading the same way. Crossings only happen under
+    /// `EdgeMode::Wrap`; other edges break ships up or turn them back. The
+    /// pass walks every shape over every live cell, so it is off by
+    /// default. Turning it on or off resets the counters.
+    pub fn set_spaceship_tracking(&mut self, enabled: bool) {
+        self.spaceships = SpaceshipTracker {
+            shapes: if enabled { ship_shapes() } else { vec![] },
+            ..SpaceshipTracker::default()
+        };
+    }
+    /// Spaceships matched after the last generation.
+    pub fn spaceship_count(&self) -> u32 {
+        self.spaceships.ships
+    }
+    /// Seam crossings counted since tracking was turned on.
+    pub fn seam_crossings(&self) -> u32 {
+        self.spaceships.crossings
+    }
+}
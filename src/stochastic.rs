@@ -0,0 +1,41 @@
+use rand::Rng;
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+impl Game {
+    // a birth or survival the rule calls for only happens with its
+    // probability; at 1 the generator isn't touched at all
+    pub(crate) fn by_chance(&mut self, col: usize, row: usize, alive: bool, next: bool) -> bool {
+        if !next || self.is_wall(col, row) || self.is_frozen(col, row) {
+            return next;
+        }
+        let probability = if alive { self.survival_probability } else { self.birth_probability };
+        probability >= 1.0 || self.rng.gen_bool(probability)
+    }
+    // applies `by_chance` to column `col` of the open generation
+    pub(crate) fn column_by_chance(&mut self, col: usize) {
+        if self.birth_probability >= 1.0 && self.survival_probability >= 1.0 {
+            return;
+        }
+        for row in 0..self.state.height() {
+            let next = self.by_chance(col, row, self.state.get(col, row), self.interim_state.get(col, row));
+            self.interim_state.set(col, row, next);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Chance that a dead cell the rule gives birth to is actually born,
+    /// drawn from the seeded generator so runs stay reproducible. 1, the
+    /// default, is the deterministic rule.
+    pub fn set_birth_probability(&mut self, probability: f64) {
+        self.birth_probability = probability.clamp(0.0, 1.0);
+    }
+    /// Chance that a live cell the rule keeps alive actually survives; the
+    /// others die. 1 by default.
+    pub fn set_survival_probability(&mut self, probability: f64) {
+        self.survival_probability = probability.clamp(0.0, 1.0);
+    }
+}
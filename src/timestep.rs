@@ -0,0 +1,115 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+
+/// Most generations one `advance_to` call catches up on; a longer backlog
+/// (a suspended tab, a debugger pause) is dropped rather than replayed.
+const MAX_CATCH_UP: u32 = 240;
+
+/// Accumulator turning elapsed time into whole generations at a fixed rate,
+/// whatever the frame rate.
+#[derive(Debug, Default)]
+pub(crate) struct FixedTimestep {
+    // 0 while off
+    pub(crate) duration_ms: f64,
+    pub(crate) last_time: Option<f64>,
+    accumulated_ms: f64,
+}
+
+impl Game {
+    pub(crate) fn timestep_enabled(&self) -> bool {
+        self.timestep.duration_ms > 0.0
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Runs a generation every `duration_ms` milliseconds of wall time
+    /// instead of computing within a frame budget: each `tick` commits as
+    /// many whole generations as fit in the time since the previous one, so
+    /// a run advances the same way at any frame rate. 0, the default, goes
+    /// back to the frame budget.
+    pub fn set_generation_duration(&mut self, duration_ms: f64) {
+        self.timestep = FixedTimestep {
+            duration_ms: duration_ms.max(0.0),
+            ..FixedTimestep::default()
+        };
+    }
+    /// `set_generation_duration` in generations per second of wall time:
+    /// below the frame rate generations are spread over several frames,
+    /// above it `tick` runs several a frame to fast-forward. 0 or less goes
+    /// back to the frame budget.
+    pub fn set_speed(&mut self, gens_per_second: f32) {
+        let duration_ms = if gens_per_second > 0.0 { 1000.0 / gens_per_second as f64 } else { 0.0 };
+        self.set_generation_duration(duration_ms);
+    }
+    /// Generations per second under `set_speed` or
+    /// `set_generation_duration`, 0 under the frame budget.
+    pub fn speed(&self) -> f32 {
+        if !self.timestep_enabled() {
+            return 0.0;
+        }
+        (1000.0 / self.timestep.duration_ms) as f32
+    }
+    /// Runs the generations due by timestamp `now_ms` (any clock, such as
+    /// `performance.now()`) under `set_generation_duration` and returns how
+    /// many ran, redrawing if any did. The first call only starts the
+    /// clock, and time spent paused doesn't count. `tick` calls it with the
+    /// performance clock; headless games pass their own timestamps.
+    pub fn advance_to(&mut self, now_ms: f64) -> u32 {
+        if !self.timestep_enabled() {
+            return 0;
+        }
+        let timestep = &mut self.timestep;
+        let last_time = timestep.last_time.replace(now_ms);
+        if !self.running {
+            return 0;
+        }
+        if let Some(last_time) = last_time {
+            timestep.accumulated_ms += (now_ms - last_time).max(0.0);
+        }
+        let due = (timestep.accumulated_ms / timestep.duration_ms).floor();
+        timestep.accumulated_ms -= due * timestep.duration_ms;
+        let count = due.min(MAX_CATCH_UP as f64) as u32;
+        if count > 0 {
+            self.run_generations(count);
+            self.redraw();
+        }
+        count
+    }
+    /// How far the time since the last generation is into the next one,
+    /// from 0 to 1, for renderers interpolating between generations.
+    pub fn timestep_alpha(&self) -> f64 {
+        if !self.timestep_enabled() {
+            return 0.0;
+        }
+        self.timestep.accumulated_ms / self.timestep.duration_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::Game;
+
+    fn seeded_run(timestamps: &[f64]) -> Game {
+        let mut game = Game::headless(40);
+        game.set_seed(168);
+        game.restart("random").unwrap();
+        // so the run draws from the generator too
+        game.set_birth_probability(0.8);
+        game.set_generation_duration(16.0);
+        for &now_ms in timestamps {
+            game.advance_to(now_ms);
+        }
+        game
+    }
+
+    #[test]
+    fn seeded_runs_repeat() {
+        let timestamps = [0.0, 5.0, 40.0, 41.5, 100.0, 333.3, 1000.0, 1016.0];
+        let (first, second) = (seeded_run(&timestamps), seeded_run(&timestamps));
+        assert_eq!(first.generation, 63);
+        assert_eq!(first.to_bytes(), second.to_bytes());
+        assert_eq!(first.run_hash(), second.run_hash());
+    }
+}
@@ -0,0 +1,84 @@
+use std::cmp::{max, min};
+use wasm_bindgen::prelude::*;
+
+use crate::edit::Edit;
+use crate::game::Game;
+
+impl Game {
+    pub(crate) fn is_wall(&self, col: usize, row: usize) -> bool {
+        self.walls
+            .get(col)
+            .and_then(|walls| walls.get(row))
+            .cloned()
+            .unwrap_or(false)
+    }
+    // like `write_cell`, a wall edit waits for or drops the open generation
+    // as the edit policy says
+    pub(crate) fn write_wall(&mut self, col: usize, row: usize, on: bool) {
+        if self.hold_edit(col, row, Edit::Wall(on)) {
+            return;
+        }
+        if self.walls.is_empty() {
+            self.walls = vec![vec![false; self.grid.height as usize]; self.grid.width as usize];
+        }
+        self.walls[col][row] = on;
+        if on {
+            self.state.set(col, row, self.wall_polarity);
+            self.invalidate_neighbour_cache();
+        }
+    }
+    // wall cells hold the polarity, which is what their neighbours count
+    pub(crate) fn apply_walls(&mut self) {
+        self.invalidate_neighbour_cache();
+        for (col, walls) in self.walls.iter().enumerate() {
+            for (row, &wall) in walls.iter().enumerate() {
+                if wall {
+                    self.state.set(col, row, self.wall_polarity);
+                }
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Turns the cell into a wall, which is never born and never dies, or
+    /// back into a regular cell. Coordinates outside the grid are ignored,
+    /// and a generation in progress is handled as the edit policy says.
+    pub fn set_wall(&mut self, x: i16, y: i16, on: bool) {
+        if !self.grid.contains(x, y) {
+            return;
+        }
+        self.write_wall(x as usize, y as usize, on);
+        self.redraw();
+    }
+    /// Turns the `width` x `height` rectangle at (`x`, `y`) into walls, a
+    /// block that never evolves and holds the wall polarity like every
+    /// other wall. The part outside the grid is ignored, and a generation in
+    /// progress is handled as the edit policy says.
+    pub fn stamp_frozen_block(&mut self, x: i16, y: i16, width: i16, height: i16) {
+        let (left, top) = (max(x, 0), max(y, 0));
+        let right = min(x.saturating_add(width), self.grid.width);
+        let bottom = min(y.saturating_add(height), self.grid.height);
+        if left >= right || top >= bottom {
+            return;
+        }
+        for col in left as usize..right as usize {
+            for row in top as usize..bottom as usize {
+                self.write_wall(col, row, true);
+            }
+        }
+        self.redraw();
+    }
+    /// Whether walls count as live neighbours (dead by default).
+    pub fn set_wall_polarity(&mut self, alive: bool) {
+        self.wall_polarity = alive;
+        self.apply_walls();
+        self.redraw();
+    }
+    /// CSS color walls are drawn with.
+    pub fn set_wall_color(&mut self, color: &str) {
+        self.wall_color = color.to_string();
+        self.redraw();
+    }
+}
@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGlProgram, WebGlRenderingContext as Gl, WebGlShader, WebGlTexture, WebGlUniformLocation};
+
+use crate::color::{hsl_to_rgb, parse_hex_color};
+use crate::game::Game;
+use crate::render::{Renderable, CANVAS_PADDING, GRADIENT};
+
+// a quad over the viewport; `cells` holds a texel per cell, top row first
+const VERTEX_SHADER: &str = r#"
+attribute vec2 position;
+varying vec2 board_position;
+void main() {
+    board_position = vec2(position.x + 1.0, 1.0 - position.y) / 2.0;
+    gl_Position = vec4(position, 0.0, 1.0);
+}
+"#;
+
+// live cells in the gradient `apply_gradient` sets up for the 2d canvas,
+// dead ones left as cleared
+const FRAGMENT_SHADER: &str = r#"
+precision mediump float;
+uniform sampler2D cells;
+uniform vec2 board_size;
+uniform vec3 gradient_from;
+uniform vec3 gradient_to;
+varying vec2 board_position;
+void main() {
+    if (texture2D(cells, board_position).r < 0.5) {
+        discard;
+    }
+    vec2 along = vec2(board_size.x, 2.0 * board_size.y);
+    float offset = clamp(dot(board_position * board_size, along) / dot(along, along), 0.0, 1.0);
+    gl_FragColor = vec4(mix(gradient_from, gradient_to, offset), 1.0);
+}
+"#;
+
+/// Draws the board with WebGL: the cells go up as a texture, a byte per
+/// cell, and a single quad samples it, so the cost of a frame hardly
+/// depends on how many cells are alive. Only live cells on the background
+/// are drawn; walls, decay, overlays and the other extras of the 2d
+/// canvas are not.
+#[derive(Debug)]
+pub(crate) struct WebGlRenderer {
+    context: Gl,
+    texture: WebGlTexture,
+    board_size: Option<WebGlUniformLocation>,
+    gradient_from: Option<WebGlUniformLocation>,
+    gradient_to: Option<WebGlUniformLocation>,
+    // the texture upload, reused between frames
+    cells: RefCell<Vec<u8>>,
+}
+
+fn compile_shader(context: &Gl, kind: u32, source: &str) -> Result<WebGlShader, JsValue> {
+    let shader = context.create_shader(kind).ok_or("unable to create a shader")?;
+    context.shader_source(&shader, source);
+    context.compile_shader(&shader);
+    match context.get_shader_parameter(&shader, Gl::COMPILE_STATUS).as_bool() {
+        Some(true) => Ok(shader),
+        _ => Err(context.get_shader_info_log(&shader).unwrap_or_else(|| "shader failed to compile".to_string()).into()),
+    }
+}
+
+fn link_program(context: &Gl) -> Result<WebGlProgram, JsValue> {
+    let program = context.create_program().ok_or("unable to create a shader program")?;
+    context.attach_shader(&program, &compile_shader(context, Gl::VERTEX_SHADER, VERTEX_SHADER)?);
+    context.attach_shader(&program, &compile_shader(context, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?);
+    context.link_program(&program);
+    match context.get_program_parameter(&program, Gl::LINK_STATUS).as_bool() {
+        Some(true) => Ok(program),
+        _ => Err(context.get_program_info_log(&program).unwrap_or_else(|| "shader program failed to link".to_string()).into()),
+    }
+}
+
+impl WebGlRenderer {
+    /// Sets up a WebGL context on `canvas`, which must not have a 2d one.
+    pub(crate) fn new(canvas: &web_sys::HtmlCanvasElement) -> Result<WebGlRenderer, JsValue> {
+        // kept so `capture_frames` can read a frame after it is shown
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"preserveDrawingBuffer".into(), &JsValue::from(true))?;
+        let context = canvas
+            .get_context_with_context_options("webgl", &options)?
+            .ok_or("webgl context unavailable")?
+            .dyn_into::<Gl>()?;
+        let program = link_program(&context)?;
+        context.use_program(Some(&program));
+
+        let quad: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+        let buffer = context.create_buffer().ok_or("unable to create a buffer")?;
+        context.bind_buffer(Gl::ARRAY_BUFFER, Some(&buffer));
+        context.buffer_data_with_array_buffer_view(Gl::ARRAY_BUFFER, &js_sys::Float32Array::from(&quad[..]), Gl::STATIC_DRAW);
+        let position = context.get_attrib_location(&program, "position");
+        if position < 0 {
+            return Err("shader program has no position attribute".into());
+        }
+        context.vertex_attrib_pointer_with_i32(position as u32, 2, Gl::FLOAT, false, 0, 0);
+        context.enable_vertex_attrib_array(position as u32);
+
+        let texture = context.create_texture().ok_or("unable to create a texture")?;
+        context.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        // boards are rarely a power of two on a side, which WebGL 1 only
+        // samples without mipmaps and with clamped edges
+        for &(parameter, value) in &[
+            (Gl::TEXTURE_MIN_FILTER, Gl::NEAREST),
+            (Gl::TEXTURE_MAG_FILTER, Gl::NEAREST),
+            (Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE),
+            (Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE),
+        ] {
+            context.tex_parameteri(Gl::TEXTURE_2D, parameter, value as i32);
+        }
+        context.pixel_storei(Gl::UNPACK_ALIGNMENT, 1);
+        context.uniform1i(context.get_uniform_location(&program, "cells").as_ref(), 0);
+
+        Ok(WebGlRenderer {
+            board_size: context.get_uniform_location(&program, "board_size"),
+            gradient_from: context.get_uniform_location(&program, "gradient_from"),
+            gradient_to: context.get_uniform_location(&program, "gradient_to"),
+            context,
+            texture,
+            cells: RefCell::new(vec![]),
+        })
+    }
+    /// Clears the whole canvas to transparent.
+    pub(crate) fn clear(&self) {
+        let context = &self.context;
+        context.viewport(0, 0, context.drawing_buffer_width(), context.drawing_buffer_height());
+        context.clear_color(0.0, 0.0, 0.0, 0.0);
+        context.clear(Gl::COLOR_BUFFER_BIT);
+    }
+}
+
+impl Renderable for WebGlRenderer {
+    // The background is only painted for `#rrggbb` and `#rgb` colors,
+    // which is all WebGL can be handed without going through a 2d canvas;
+    // other colors paint white.
+    fn render(&self, game: &Game) {
+        let context = &self.context;
+        self.clear();
+        if !game.transparent_background {
+            let (red, green, blue) = parse_hex_color(&game.background_color).unwrap_or((255, 255, 255));
+            context.clear_color(red as f32 / 255.0, green as f32 / 255.0, blue as f32 / 255.0, 1.0);
+            context.clear(Gl::COLOR_BUFFER_BIT);
+        }
+
+        let (width, height) = (game.grid.width as usize, game.grid.height as usize);
+        let mut cells = self.cells.borrow_mut();
+        cells.clear();
+        cells.resize(width * height, 0);
+        for (x, y) in game.state.live_cells() {
+            cells[y * width + x] = 255;
+        }
+        context.bind_texture(Gl::TEXTURE_2D, Some(&self.texture));
+        let upload = js_sys::Uint8Array::from(&cells[..]);
+        let uploaded = context.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+            Gl::TEXTURE_2D, 0, Gl::LUMINANCE as i32, width as i32, height as i32, 0, Gl::LUMINANCE, Gl::UNSIGNED_BYTE,
+            Some(&upload));
+        if uploaded.is_err() {
+            return;
+        }
+
+        // the board sits inside the padding, and WebGL counts rows from
+        // the bottom of the canvas
+        let (board_width, board_height) = ((game.grid.cell * game.grid.width) as f64, (game.grid.cell * game.grid.height) as f64);
+        let pixels = |css: f64| (css * game.pixel_ratio).round() as i32;
+        let bottom = context.drawing_buffer_height() - pixels(CANVAS_PADDING) - pixels(board_height);
+        context.viewport(pixels(CANVAS_PADDING), bottom, pixels(board_width), pixels(board_height));
+        context.uniform2f(self.board_size.as_ref(), board_width as f32, board_height as f32);
+        let stop = |index: usize| {
+            let (_, hue, saturation, lightness) = GRADIENT[index];
+            let (red, green, blue) = hsl_to_rgb(hue + game.hue_offset, saturation, lightness);
+            (red as f32 / 255.0, green as f32 / 255.0, blue as f32 / 255.0)
+        };
+        let ((from_red, from_green, from_blue), (to_red, to_green, to_blue)) = (stop(0), stop(1));
+        context.uniform3f(self.gradient_from.as_ref(), from_red, from_green, from_blue);
+        context.uniform3f(self.gradient_to.as_ref(), to_red, to_green, to_blue);
+        context.draw_arrays(Gl::TRIANGLE_STRIP, 0, 4);
+    }
+}